@@ -1,3 +1,5 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
@@ -103,6 +105,9 @@ impl DRGInstallation {
             .join("Paks")
             .join(self.installation_type.main_pak_name())
     }
+    pub fn game_version(&self) -> Result<GameVersion> {
+        GameVersion::from_pak_path(self.main_pak())
+    }
     pub fn modio_directory(&self) -> Option<PathBuf> {
         match self.installation_type {
             DRGInstallationType::Steam => {
@@ -123,3 +128,36 @@ impl DRGInstallation {
         }
     }
 }
+
+/// Version of the installed game, such as `1.38.89736.0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameVersion(pub String);
+
+impl GameVersion {
+    /// Reads `ProjectVersion` from the project settings in the game pak.
+    pub fn from_pak_path<P: AsRef<Path>>(pak: P) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(pak.as_ref())?);
+        let pak = repak::PakBuilder::new().reader(&mut reader)?;
+        let mut ini = String::new();
+        pak.get("FSD/Config/DefaultGame.ini", &mut reader)?
+            .as_slice()
+            .read_to_string(&mut ini)
+            .context("DefaultGame.ini is not valid UTF-8")?;
+        ini.lines()
+            .filter_map(|line| line.trim().split_once('='))
+            .find(|(key, _)| key.trim() == "ProjectVersion")
+            .map(|(_, value)| Self(value.trim().to_string()))
+            .context("ProjectVersion not found in DefaultGame.ini")
+    }
+    /// Whether a version tag such as `1.38` covers this version.
+    pub fn matches_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim();
+        self.0 == tag || self.0.starts_with(&format!("{tag}."))
+    }
+}
+
+impl std::fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
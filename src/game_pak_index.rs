@@ -0,0 +1,168 @@
+//! Hashes of the files of the game pak, to tell which mod files are identical to the game's.
+//! Hashing the whole pak takes minutes, so the index is kept in the cache directory and only
+//! rebuilt when the game pak changes, usually after a game update.
+
+use std::collections::HashMap;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{anyhow, Result};
+use fs_err as fs;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::mod_lints::resolve_path;
+
+/// Bumped when the contents of the index change, to rebuild indexes written by older versions.
+const INDEX_VERSION: u32 = 1;
+
+/// Bytes hashed at the start and at the end of the pak, which include its footer.
+const HEADER_SIZE: u64 = 64 * 1024;
+
+/// Identifies a version of the game pak.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct GamePakKey {
+    path: PathBuf,
+    size: u64,
+    mtime_secs: u64,
+    mtime_nanos: u32,
+    /// Hex SHA-256 of the start and the end of the pak, for changes that keep size and mtime.
+    header_hash: String,
+}
+
+impl GamePakKey {
+    fn new(game_pak_path: &Path) -> Result<Self> {
+        let path = fs::canonicalize(game_pak_path)?;
+        let mut file = fs::File::open(&path)?;
+        let metadata = file.metadata()?;
+        let size = metadata.len();
+        let mtime = metadata.modified()?.duration_since(UNIX_EPOCH)?;
+
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0; HEADER_SIZE.min(size) as usize];
+        file.read_exact(&mut buf)?;
+        hasher.update(&buf);
+        file.seek(SeekFrom::End(-(buf.len() as i64)))?;
+        file.read_exact(&mut buf)?;
+        hasher.update(&buf);
+
+        Ok(Self {
+            path,
+            size,
+            mtime_secs: mtime.as_secs(),
+            mtime_nanos: mtime.subsec_nanos(),
+            header_hash: hex::encode(hasher.finalize()),
+        })
+    }
+
+    /// Path of the index of this game pak in `cache_dir`, one per game pak path.
+    fn index_path(&self, cache_dir: &Path) -> PathBuf {
+        let name = hex::encode(Sha256::digest(self.path.to_string_lossy().as_bytes()));
+        cache_dir
+            .join("game_pak_index")
+            .join(format!("{name}.json"))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+    key: GamePakKey,
+    hashes: HashMap<String, String>,
+}
+
+/// Hex SHA-256 of every file of a game pak, by lowercase path relative to the game root.
+#[derive(Debug, Clone, Default)]
+pub struct GamePakIndex {
+    hashes: HashMap<String, String>,
+}
+
+impl GamePakIndex {
+    /// Hashes every file of the game pak.
+    pub fn build(game_pak_path: &Path) -> Result<Self> {
+        let mut reader = BufReader::new(fs::File::open(game_pak_path)?);
+        let pak = repak::PakBuilder::new().reader(&mut reader)?;
+
+        let files = pak
+            .files()
+            .into_iter()
+            .filter_map(|f| {
+                let path = resolve_path(pak.mount_point(), &f).ok()?;
+                Some((path.to_ascii_lowercase(), f))
+            })
+            .collect::<Vec<_>>();
+
+        let hashes = files
+            .par_iter()
+            .map_init(
+                || fs::File::open(game_pak_path),
+                |file, (path, f)| -> Result<(String, String)> {
+                    let file = file
+                        .as_ref()
+                        .map_err(|e| anyhow!("failed to open game pak: {e}"))?;
+                    let mut hasher = Sha256::new();
+                    pak.read_file(f, &mut BufReader::new(file), &mut hasher)?;
+                    Ok((path.clone(), hex::encode(hasher.finalize())))
+                },
+            )
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        Ok(Self { hashes })
+    }
+
+    /// Loads the index of the game pak from `cache_dir`, building and saving it if there is none
+    /// or the game pak changed since it was built.
+    pub fn load_or_build(game_pak_path: &Path, cache_dir: &Path) -> Result<Self> {
+        let key = GamePakKey::new(game_pak_path)?;
+        let index_path = key.index_path(cache_dir);
+
+        if let Some(index) = fs::read(&index_path)
+            .ok()
+            .and_then(|data| serde_json::from_slice::<IndexFile>(&data).ok())
+            && index.version == INDEX_VERSION
+            && index.key == key
+        {
+            return Ok(Self {
+                hashes: index.hashes,
+            });
+        }
+
+        info!("building hash index of {}", game_pak_path.display());
+        let index = IndexFile {
+            version: INDEX_VERSION,
+            key,
+            hashes: Self::build(game_pak_path)?.hashes,
+        };
+        // the index only saves time, so failing to save it isn't fatal
+        if let Err(e) = Self::save(&index_path, &index) {
+            warn!("failed to save game pak hash index: {e:#}");
+        }
+
+        Ok(Self {
+            hashes: index.hashes,
+        })
+    }
+
+    fn save(index_path: &Path, index: &IndexFile) -> Result<()> {
+        fs::create_dir_all(index_path.parent().unwrap())?;
+        let tmp = index_path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(index)?)?;
+        fs::rename(tmp, index_path)?;
+        Ok(())
+    }
+
+    /// Hex SHA-256 of the game file at `normalized_path`, the lowercase path relative to the game
+    /// root.
+    pub fn get(&self, normalized_path: &str) -> Option<&str> {
+        self.hashes.get(normalized_path).map(String::as_str)
+    }
+
+    /// Whether `data` is identical to the game file at `normalized_path`.
+    pub fn is_unmodified(&self, normalized_path: &str, data: &[u8]) -> bool {
+        self.get(normalized_path)
+            .is_some_and(|hash| hash == hex::encode(Sha256::digest(data)))
+    }
+}
@@ -13,11 +13,13 @@ use tokio::{
     sync::mpsc::{self, Sender},
     task::JoinHandle,
 };
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::gui::LastAction;
-use crate::integrate::{IntegrationErr, IntegrationErrKind};
-use crate::mod_lints::{LintId, LintReport};
+use crate::integrate::{
+    IntegrationErr, IntegrationErrKind, IntegrationOptions, IntegrationReport,
+};
+use crate::mod_lints::{run_lints_with_ctxt, LintCtxt, LintId, LintReport, LintSuppression};
 use crate::state::{ModData_v0_2_0 as ModData, ModOrGroup};
 use crate::{
     error::IntegrationError,
@@ -46,6 +48,7 @@ pub enum Message {
     CheckUpdates(CheckUpdates),
     LintMods(LintMods),
     FetchModDetails(FetchModDetails),
+    FetchModPaks(FetchModPaks),
 }
 
 impl Message {
@@ -58,6 +61,7 @@ impl Message {
             Self::CheckUpdates(msg) => msg.receive(app),
             Self::LintMods(msg) => msg.receive(app),
             Self::FetchModDetails(msg) => msg.receive(app),
+            Self::FetchModPaks(msg) => msg.receive(app),
         }
     }
 }
@@ -149,6 +153,9 @@ impl ResolveMods {
                                     required: info.suggested_require,
                                     enabled: true,
                                     priority: 0,
+                                    disabled_paks: Default::default(),
+                                    settings: Default::default(),
+                                    fixed_pak: None,
                                 }),
                             );
                         }
@@ -179,15 +186,16 @@ impl ResolveMods {
 #[derive(Debug)]
 pub struct Integrate {
     rid: RequestID,
-    result: Result<(), IntegrationErr>,
+    result: Result<IntegrationReport, IntegrationErr>,
 }
 
 impl Integrate {
     pub fn send(
         rc: &mut RequestCounter,
         store: Arc<ModStore>,
-        mods: Vec<ModSpecification>,
+        mods: Vec<ModConfig>,
         fsd_pak: PathBuf,
+        options: IntegrationOptions,
         tx: Sender<Message>,
         ctx: egui::Context,
     ) -> MessageHandle<HashMap<ModSpecification, SpecFetchProgress>> {
@@ -195,7 +203,16 @@ impl Integrate {
         MessageHandle {
             rid,
             handle: tokio::task::spawn(async move {
-                let res = integrate_async(store, ctx.clone(), mods, fsd_pak, rid, tx.clone()).await;
+                let res = integrate_async(
+                    store,
+                    ctx.clone(),
+                    mods,
+                    fsd_pak,
+                    options,
+                    rid,
+                    tx.clone(),
+                )
+                .await;
                 tx.send(Message::Integrate(Integrate { rid, result: res }))
                     .await
                     .unwrap();
@@ -207,9 +224,14 @@ impl Integrate {
     fn receive(self, app: &mut App) {
         if Some(self.rid) == app.integrate_rid.as_ref().map(|r| r.rid) {
             match self.result {
-                Ok(()) => {
+                Ok(report) => {
                     info!("integration complete");
-                    app.last_action = Some(LastAction::success("DLL hook and mod bundle installed".to_string()));
+                    if report.is_empty() {
+                        app.last_action = Some(LastAction::success("DLL hook and mod bundle installed".to_string()));
+                    } else {
+                        app.last_action = Some(LastAction::success("DLL hook and mod bundle installed, see integration report".to_string()));
+                        app.integration_report = Some(report);
+                    }
                 }
                 Err(IntegrationErr { mod_ctxt, kind }) => match kind {
                     IntegrationErrKind::Generic(e) => match e.downcast::<IntegrationError>() {
@@ -378,15 +400,20 @@ impl CheckUpdates {
 async fn integrate_async(
     store: Arc<ModStore>,
     ctx: egui::Context,
-    mod_specs: Vec<ModSpecification>,
+    mod_configs: Vec<ModConfig>,
     fsd_pak: PathBuf,
+    options: IntegrationOptions,
     rid: RequestID,
     message_tx: Sender<Message>,
-) -> Result<(), IntegrationErr> {
+) -> Result<IntegrationReport, IntegrationErr> {
     let update = false;
 
-    let mods = store
-        .resolve_mods(&mod_specs, update)
+    let mod_specs = mod_configs
+        .iter()
+        .map(|mc| mc.spec.clone())
+        .collect::<Vec<_>>();
+
+    let mods = crate::resolve_mods_for_game(&store, &fsd_pak, &mod_specs, update, &options)
         .await
         .map_err(|e| IntegrationErr {
             mod_ctxt: None,
@@ -432,16 +459,23 @@ async fn integrate_async(
             kind: IntegrationErrKind::Generic(e),
         })?;
 
-    tokio::task::spawn_blocking(|| {
-        crate::integrate::integrate(fsd_pak, to_integrate.into_iter().zip(paths).collect())
+    tokio::task::spawn_blocking(move || {
+        crate::integrate::integrate(
+            fsd_pak,
+            to_integrate
+                .into_iter()
+                .zip(mod_configs)
+                .zip(paths)
+                .map(|((info, config), path)| (info, config, path))
+                .collect(),
+            &options,
+        )
     })
     .await
     .map_err(|e| IntegrationErr {
         mod_ctxt: None,
         kind: IntegrationErrKind::Generic(e.into()),
-    })??;
-
-    Ok(())
+    })?
 }
 
 #[derive(Debug)]
@@ -451,30 +485,69 @@ pub struct LintMods {
 }
 
 impl LintMods {
+    #[allow(clippy::too_many_arguments)]
     pub fn send(
         rc: &mut RequestCounter,
         store: Arc<ModStore>,
-        mods: Vec<ModSpecification>,
+        mod_configs: Vec<ModConfig>,
+        candidate_mods: Vec<ModSpecification>,
+        disabled_paks: HashMap<ModSpecification, BTreeSet<String>>,
+        mod_infos: HashMap<ModSpecification, ModInfo>,
         enabled_lints: BTreeSet<LintId>,
+        suppressions: BTreeSet<LintSuppression>,
         game_pak_path: Option<PathBuf>,
+        cache_dir: PathBuf,
         tx: Sender<Message>,
         ctx: egui::Context,
     ) -> MessageHandle<()> {
         let rid = rc.next();
 
         let handle = tokio::task::spawn(async move {
+            let mods = mod_configs
+                .iter()
+                .map(|mc| mc.spec.clone())
+                .collect::<Vec<_>>();
             let paths_res =
-                resolve_async_ordered(store, ctx.clone(), mods.clone(), rid, tx.clone()).await;
-            let mod_path_pairs_res =
-                paths_res.map(|paths| mods.into_iter().zip(paths).collect::<Vec<_>>());
+                resolve_async_ordered(store.clone(), ctx.clone(), mods, rid, tx.clone()).await;
+            // mods cleaned by an earlier fix are linted as they are integrated
+            let mod_path_pairs_res = paths_res.map(|paths| {
+                mod_configs
+                    .iter()
+                    .zip(paths)
+                    .map(|(mc, path)| (mc.spec.clone(), mc.file_path(&path)))
+                    .collect::<Vec<_>>()
+            });
+
+            // candidates only improve the report, so failing to fetch them isn't fatal
+            let candidate_pairs = if candidate_mods.is_empty() {
+                vec![]
+            } else {
+                match resolve_async_ordered(
+                    store,
+                    ctx.clone(),
+                    candidate_mods.clone(),
+                    rid,
+                    tx.clone(),
+                )
+                .await
+                {
+                    Ok(paths) => candidate_mods.into_iter().zip(paths).collect(),
+                    Err(e) => {
+                        warn!("failed to resolve candidate mods for linting: {e:#}");
+                        vec![]
+                    }
+                }
+            };
 
             let report_res = match mod_path_pairs_res {
                 Ok(pairs) => tokio::task::spawn_blocking(move || {
-                    crate::mod_lints::run_lints(
-                        &enabled_lints,
-                        pairs.into_iter().collect(),
-                        game_pak_path,
-                    )
+                    let lint_ctxt = LintCtxt::init(pairs.into_iter().collect(), game_pak_path)?
+                        .with_candidate_mods(candidate_pairs.into_iter().collect())
+                        .with_disabled_paks(disabled_paks)
+                        .with_mod_infos(mod_infos)
+                        .with_suppressions(suppressions)
+                        .with_cache_dir(cache_dir);
+                    run_lints_with_ctxt(&enabled_lints, &lint_ctxt)
                 })
                 .await
                 .unwrap(),
@@ -673,3 +746,65 @@ async fn fetch_modio_mod_details(oauth_token: String, modio_id: u32) -> Result<M
         thumbnail,
     })
 }
+
+#[derive(Debug)]
+pub struct FetchModPaks {
+    rid: RequestID,
+    spec: ModSpecification,
+    result: Result<Vec<String>>,
+}
+
+impl FetchModPaks {
+    pub fn send(
+        rc: &mut RequestCounter,
+        ctx: &egui::Context,
+        tx: Sender<Message>,
+        store: Arc<ModStore>,
+        spec: ModSpecification,
+    ) -> MessageHandle<()> {
+        let rid = rc.next();
+        let ctx = ctx.clone();
+
+        MessageHandle {
+            rid,
+            handle: tokio::task::spawn(async move {
+                let result = fetch_mod_paks(store, spec.clone()).await;
+                tx.send(Message::FetchModPaks(FetchModPaks { rid, spec, result }))
+                    .await
+                    .unwrap();
+                ctx.request_repaint();
+            }),
+            state: (),
+        }
+    }
+
+    fn receive(self, app: &mut App) {
+        if let Some(req) = app.fetch_mod_paks_rid.get(&self.spec)
+            && req.rid == self.rid
+        {
+            match self.result {
+                Ok(paks) => {
+                    info!("fetch mod paks successful");
+                    app.mod_paks.insert(self.spec.clone(), paks);
+                }
+                Err(e) => {
+                    error!("fetch mod paks failed");
+                    error!("{:#?}", e);
+                    app.last_action = Some(LastAction::failure("Failed to read mod archive".to_string()));
+                }
+            }
+            app.fetch_mod_paks_rid.remove(&self.spec);
+        }
+    }
+}
+
+async fn fetch_mod_paks(store: Arc<ModStore>, spec: ModSpecification) -> Result<Vec<String>> {
+    let (_, info) = store.resolve_mod(spec, false).await?;
+    let path = store.fetch_mod(&info.resolution, false, None).await?;
+    tokio::task::spawn_blocking(move || {
+        crate::list_paks_in_data(Box::new(std::io::BufReader::new(fs_err::File::open(
+            path,
+        )?)))
+    })
+    .await?
+}
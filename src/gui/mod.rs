@@ -7,7 +7,7 @@ mod toggle_switch;
 //#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 use std::cmp::Ordering;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::BTreeSet;
 use std::ops::RangeInclusive;
 use std::time::{Instant, SystemTime};
 use std::{
@@ -33,11 +33,14 @@ use tokio::{
 };
 use tracing::{debug, trace};
 
-use crate::mod_lints::{LintId, LintReport, SplitAssetPair};
+use crate::mod_lints::{fix_mods, LintDefinition, LintId, LintReport, LintSuppression, Severity};
 use crate::state::SortingConfig;
 use crate::Dirs;
 use crate::{
-    integrate::uninstall,
+    integrate::{
+        modio_ini_backups, plan_modio_ini_update, restore_modio_ini, uninstall, BundleCompression,
+        DiffLine, IntegrationOptions, IntegrationPatch, IntegrationReport, ModioIniUpdate,
+    },
     is_drg_pak,
     providers::{
         ApprovalStatus, FetchProgress, ModInfo, ModSpecification, ModStore, ModioTags,
@@ -167,29 +170,19 @@ pub struct App {
     lint_report_window: Option<WindowLintReport>,
     lint_report: Option<LintReport>,
     lints_toggle_window: Option<WindowLintsToggle>,
-    lint_options: LintOptions,
+    enabled_lints: BTreeSet<LintId>,
+    integration_report: Option<IntegrationReport>,
+    modio_ini_window: Option<WindowModioIni>,
     update_cmark_cache: CommonMarkCache,
     pending_delete: Option<usize>,
     detailed_mod_info_windows: HashMap<u32, WindowDetailedModInfo>,
     mod_details: HashMap<u32, ModDetails>,
     fetch_mod_details_rid: HashMap<u32, MessageHandle<()>>,
+    mod_paks: HashMap<ModSpecification, Vec<String>>,
+    fetch_mod_paks_rid: HashMap<ModSpecification, MessageHandle<()>>,
     mod_details_thumbnail_texture_handle: HashMap<u32, egui::TextureHandle>,
 }
 
-#[derive(Default)]
-struct LintOptions {
-    archive_with_multiple_paks: bool,
-    archive_with_only_non_pak_files: bool,
-    asset_register_bin: bool,
-    conflicting: bool,
-    empty_archive: bool,
-    outdated_pak_version: bool,
-    shader_files: bool,
-    non_asset_files: bool,
-    split_asset_pairs: bool,
-    unmodified_game_assets: bool,
-}
-
 struct LastAction {
     timestamp: Instant,
     status: LastActionStatus,
@@ -275,12 +268,16 @@ impl App {
             lint_report_window: None,
             lint_report: None,
             lints_toggle_window: None,
-            lint_options: LintOptions::default(),
+            enabled_lints: BTreeSet::new(),
+            integration_report: None,
+            modio_ini_window: None,
             update_cmark_cache: Default::default(),
             pending_delete: None,
             detailed_mod_info_windows: HashMap::default(),
             mod_details: HashMap::default(),
             fetch_mod_details_rid: HashMap::default(),
+            mod_paks: HashMap::default(),
+            fetch_mod_paks_rid: HashMap::default(),
             mod_details_thumbnail_texture_handle: HashMap::default(),
         })
     }
@@ -638,6 +635,89 @@ impl App {
                         });
                     }
 
+                    if self.mod_paks.get(&mc.spec).map_or(true, |paks| paks.len() > 1) {
+                        ui.menu_button("\u{1F4E6}", |ui| {
+                            match self.mod_paks.get(&mc.spec) {
+                                Some(paks) => {
+                                    for pak in paks {
+                                        let mut enabled = !mc.disabled_paks.contains(pak);
+                                        if ui.checkbox(&mut enabled, pak).changed() {
+                                            if enabled {
+                                                mc.disabled_paks.remove(pak);
+                                            } else {
+                                                mc.disabled_paks.insert(pak.clone());
+                                            }
+                                            ctx.needs_save = true;
+                                        }
+                                    }
+                                }
+                                None => {
+                                    if !self.fetch_mod_paks_rid.contains_key(&mc.spec) {
+                                        self.fetch_mod_paks_rid.insert(mc.spec.clone(), message::FetchModPaks::send(
+                                            &mut self.request_counter,
+                                            ui.ctx(),
+                                            self.tx.clone(),
+                                            self.state.store.clone(),
+                                            mc.spec.clone(),
+                                        ));
+                                    }
+                                    ui.spinner();
+                                }
+                            }
+                        })
+                        .response
+                        .on_hover_text_at_pointer("Paks to integrate");
+                    }
+
+                    if mc.fixed_pak.is_some()
+                        && ui
+                            .button("\u{1F9F9}")
+                            .on_hover_text_at_pointer("Using the pak cleaned by the lint fixes\nClick to use the mod's own file again")
+                            .clicked()
+                    {
+                        mc.fixed_pak = None;
+                        ctx.needs_save = true;
+                    }
+
+                    ui.menu_button("\u{2699}", |ui| {
+                        let mut remove = None;
+                        egui::Grid::new("mod_settings").num_columns(3).show(ui, |ui| {
+                            for (key, value) in mc.settings.iter_mut() {
+                                ui.label(key);
+                                if ui.text_edit_singleline(value).changed() {
+                                    ctx.needs_save = true;
+                                }
+                                if ui.button("\u{1F5D1}").on_hover_text_at_pointer("Remove").clicked() {
+                                    remove = Some(key.clone());
+                                }
+                                ui.end_row();
+                            }
+                        });
+                        if let Some(key) = remove {
+                            mc.settings.remove(&key);
+                            ctx.needs_save = true;
+                        }
+
+                        let id = egui::Id::new(("new_mod_setting", &mc.spec.url));
+                        let mut new_key: String = ui.data(|data| data.get_temp(id)).unwrap_or_default();
+                        ui.horizontal(|ui| {
+                            ui.add(egui::TextEdit::singleline(&mut new_key).hint_text("New setting"));
+                            if ui
+                                .add_enabled(
+                                    !new_key.is_empty() && !mc.settings.contains_key(&new_key),
+                                    egui::Button::new("Add"),
+                                )
+                                .clicked()
+                            {
+                                mc.settings.insert(std::mem::take(&mut new_key), String::new());
+                                ctx.needs_save = true;
+                            }
+                        });
+                        ui.data_mut(|data| data.insert_temp(id, new_key));
+                    })
+                    .response
+                    .on_hover_text_at_pointer("Settings passed to the mod in game");
+
                     if mc.enabled {
                         let is_duplicate = enabled_specs.iter().any(|(i, spec)| {
                             Some(row_index) != *i && info.spec.satisfies_dependency(spec)
@@ -1191,6 +1271,35 @@ impl App {
                         });
                         ui.end_row();
 
+                        let mut job = LayoutJob::default();
+                        job.append(
+                            "mod.io config:",
+                            0.0,
+                            TextFormat {
+                                color: ui.visuals().text_color(),
+                                underline: Stroke::new(1.0, ui.visuals().text_color()),
+                                ..Default::default()
+                            },
+                        );
+                        ui.label(job)
+                            .on_hover_cursor(egui::CursorIcon::Help)
+                            .on_hover_text("Backups of the official mod.io integration config made when uninstalling");
+                        ui.add_enabled_ui(self.state.config.drg_pak_path.is_some(), |ui| {
+                            if ui.button("Backups...").clicked() {
+                                if let Some(pak_path) = &self.state.config.drg_pak_path {
+                                    match WindowModioIni::new(pak_path.clone(), None) {
+                                        Ok(window) => self.modio_ini_window = Some(window),
+                                        Err(e) => {
+                                            self.last_action = Some(LastAction::failure(format!(
+                                                "Failed to list mod.io config backups: {e}"
+                                            )))
+                                        }
+                                    }
+                                }
+                            }
+                        });
+                        ui.end_row();
+
                         ui.add_space(1.);
                         ui.end_row();
 
@@ -1264,6 +1373,132 @@ impl App {
                         ui.add_space(1.);
                         ui.end_row();
 
+                        ui.heading("Mod Bundle");
+                        ui.end_row();
+
+                        let mut job = LayoutJob::default();
+                        job.append(
+                            "Compression:",
+                            0.0,
+                            TextFormat {
+                                color: ui.visuals().text_color(),
+                                underline: Stroke::new(1.0, ui.visuals().text_color()),
+                                ..Default::default()
+                            },
+                        );
+                        ui.label(job)
+                            .on_hover_cursor(egui::CursorIcon::Help)
+                            .on_hover_text("Compression of the generated mod bundle. \"None\" is the fastest to build and load but takes the most disk space");
+                        ui.horizontal(|ui| {
+                            let config = &mut self.state.config;
+
+                            egui::ComboBox::from_id_source("bundle_compression")
+                                .selected_text(config.bundle_compression.as_str())
+                                .show_ui(ui, |ui| {
+                                    let mut changed = false;
+                                    for compression in [
+                                        BundleCompression::None,
+                                        BundleCompression::Zlib,
+                                        #[cfg(feature = "oodle")]
+                                        BundleCompression::Oodle,
+                                    ] {
+                                        changed |= ui.selectable_value(&mut config.bundle_compression, compression, compression.as_str()).changed();
+                                    }
+                                    if changed {
+                                        config.save().unwrap();
+                                    }
+                                });
+                        });
+                        ui.end_row();
+
+                        let mut job = LayoutJob::default();
+                        job.append(
+                            "Merge DataTables:",
+                            0.0,
+                            TextFormat {
+                                color: ui.visuals().text_color(),
+                                underline: Stroke::new(1.0, ui.visuals().text_color()),
+                                ..Default::default()
+                            },
+                        );
+                        ui.label(job)
+                            .on_hover_cursor(egui::CursorIcon::Help)
+                            .on_hover_text("Merge the rows of DataTables modified by several mods instead of only using the version of the mod with the highest priority");
+                        ui.horizontal(|ui| {
+                            let config = &mut self.state.config;
+                            if ui
+                                .add(toggle_switch(&mut config.merge_datatables))
+                                .changed()
+                            {
+                                config.save().unwrap();
+                            }
+                        });
+                        ui.end_row();
+
+                        let mut job = LayoutJob::default();
+                        job.append(
+                            "Prefer compatible versions:",
+                            0.0,
+                            TextFormat {
+                                color: ui.visuals().text_color(),
+                                underline: Stroke::new(1.0, ui.visuals().text_color()),
+                                ..Default::default()
+                            },
+                        );
+                        ui.label(job)
+                            .on_hover_cursor(egui::CursorIcon::Help)
+                            .on_hover_text("Integrate the newest version named after the installed game version of mod.io mods whose latest version isn't made for it. Mods pinned to a version are left alone");
+                        ui.horizontal(|ui| {
+                            let config = &mut self.state.config;
+                            if ui
+                                .add(toggle_switch(&mut config.prefer_compatible_modfiles))
+                                .changed()
+                            {
+                                config.save().unwrap();
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.add_space(1.);
+                        ui.end_row();
+
+                        ui.heading("Integration Patches")
+                            .on_hover_text("Changes made to game assets when integrating, enabled per profile");
+                        ui.end_row();
+
+                        for patch in IntegrationPatch::iter() {
+                            let mut job = LayoutJob::default();
+                            job.append(
+                                &format!("{}:", patch.name()),
+                                0.0,
+                                TextFormat {
+                                    color: ui.visuals().text_color(),
+                                    underline: Stroke::new(1.0, ui.visuals().text_color()),
+                                    ..Default::default()
+                                },
+                            );
+                            ui.label(job)
+                                .on_hover_cursor(egui::CursorIcon::Help)
+                                .on_hover_text(patch.description());
+                            ui.horizontal(|ui| {
+                                let mod_data = &mut self.state.mod_data;
+                                let disabled_patches = &mut mod_data.get_active_profile_mut().disabled_patches;
+                                let mut enabled = !disabled_patches.contains(patch.id());
+                                if ui.add(toggle_switch(&mut enabled)).changed() {
+                                    if enabled {
+                                        disabled_patches.remove(patch.id());
+                                    } else {
+                                        disabled_patches.insert(patch.id().to_string());
+                                    }
+                                    mod_data.save().unwrap();
+                                }
+                            });
+                            ui.end_row();
+                        }
+
+                        ui.add_space(1.);
+                        ui.end_row();
+
                         ui.heading("UI Theme");
                         ui.end_row();
 
@@ -1355,55 +1590,26 @@ impl App {
                             ui.heading("Enabled?");
                             ui.end_row();
 
-                            ui.label("Archive with multiple paks");
-                            ui.add(toggle_switch(
-                                &mut self.lint_options.archive_with_multiple_paks,
-                            ));
-                            ui.end_row();
-
-                            ui.label("Archive with only non-pak files");
-                            ui.add(toggle_switch(
-                                &mut self.lint_options.archive_with_only_non_pak_files,
-                            ));
-                            ui.end_row();
-
-                            ui.label("Mods containing AssetRegister.bin");
-                            ui.add(toggle_switch(&mut self.lint_options.asset_register_bin));
-                            ui.end_row();
-
-                            ui.label("Mods containing conflicting files");
-                            ui.add(toggle_switch(&mut self.lint_options.conflicting));
-                            ui.end_row();
-
-                            ui.label("Mods containing empty archives");
-                            ui.add(toggle_switch(&mut self.lint_options.empty_archive));
-                            ui.end_row();
-
-                            ui.label("Mods containing oudated pak version");
-                            ui.add(toggle_switch(&mut self.lint_options.outdated_pak_version));
-                            ui.end_row();
-
-                            ui.label("Mods containing shader files");
-                            ui.add(toggle_switch(&mut self.lint_options.shader_files));
-                            ui.end_row();
-
-                            ui.label("Mods containing non-asset files");
-                            ui.add(toggle_switch(&mut self.lint_options.non_asset_files));
-                            ui.end_row();
-
-                            ui.label("Mods containing split {uexp, uasset} pairs");
-                            ui.add(toggle_switch(&mut self.lint_options.split_asset_pairs));
-                            ui.end_row();
-
-                            ui.label("Mods containing unmodified game assets");
-                            ui.add_enabled(
-                                self.state.config.drg_pak_path.is_some(),
-                                toggle_switch(&mut self.lint_options.unmodified_game_assets),
-                            )
-                            .on_disabled_hover_text(
-                                "This lint requires DRG pak path to be specified",
-                            );
-                            ui.end_row();
+                            for lint in LintDefinition::all() {
+                                ui.label(lint.title).on_hover_text(lint.description);
+                                let mut enabled = self.enabled_lints.contains(&lint.id);
+                                let available =
+                                    !lint.requires_game_pak || self.state.config.drg_pak_path.is_some();
+                                if ui
+                                    .add_enabled(available, toggle_switch(&mut enabled))
+                                    .on_disabled_hover_text(
+                                        "This lint requires DRG pak path to be specified",
+                                    )
+                                    .changed()
+                                {
+                                    if enabled {
+                                        self.enabled_lints.insert(lint.id);
+                                    } else {
+                                        self.enabled_lints.remove(&lint.id);
+                                    }
+                                }
+                                ui.end_row();
+                            }
                         });
                     });
 
@@ -1421,63 +1627,89 @@ impl App {
                             )
                             .clicked()
                         {
-                            let lint_options = BTreeMap::from([
-                                (
-                                    LintId::ARCHIVE_WITH_MULTIPLE_PAKS,
-                                    self.lint_options.archive_with_multiple_paks,
-                                ),
-                                (
-                                    LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES,
-                                    self.lint_options.archive_with_only_non_pak_files,
-                                ),
-                                (
-                                    LintId::ASSET_REGISTRY_BIN,
-                                    self.lint_options.asset_register_bin,
-                                ),
-                                (LintId::CONFLICTING, self.lint_options.conflicting),
-                                (LintId::EMPTY_ARCHIVE, self.lint_options.empty_archive),
-                                (
-                                    LintId::OUTDATED_PAK_VERSION,
-                                    self.lint_options.outdated_pak_version,
-                                ),
-                                (LintId::SHADER_FILES, self.lint_options.shader_files),
-                                (LintId::NON_ASSET_FILES, self.lint_options.non_asset_files),
-                                (
-                                    LintId::SPLIT_ASSET_PAIRS,
-                                    self.lint_options.split_asset_pairs,
-                                ),
-                                (
-                                    LintId::UNMODIFIED_GAME_ASSETS,
-                                    self.lint_options.unmodified_game_assets,
-                                ),
-                            ]);
-
-                            trace!(?lint_options);
-
-                            let mut mods = Vec::new();
+                            let enabled_lints = self
+                                .enabled_lints
+                                .iter()
+                                .copied()
+                                .filter(|id| {
+                                    LintDefinition::get(*id).is_some_and(|lint| {
+                                        !lint.requires_game_pak
+                                            || self.state.config.drg_pak_path.is_some()
+                                    })
+                                })
+                                .collect::<BTreeSet<_>>();
+
+                            trace!(?enabled_lints);
+
+                            // in load order, like when integrating
+                            let mut mod_configs = Vec::new();
                             self.state.mod_data.for_each_enabled_mod(
                                 &self.state.mod_data.active_profile,
                                 |mc| {
-                                    mods.push(mc.spec.clone());
+                                    mod_configs.push(mc.clone());
                                 },
                             );
+                            mod_configs.sort_by_key(|k| -k.priority);
+                            let mods = mod_configs
+                                .iter()
+                                .map(|mc| mc.spec.clone())
+                                .collect::<Vec<_>>();
+
+                            // paks left out when integrating are left out of the lints as well
+                            let mut disabled_paks = mod_configs
+                                .iter()
+                                .filter(|mc| !mc.disabled_paks.is_empty())
+                                .map(|mc| (mc.spec.clone(), mc.disabled_paks.clone()))
+                                .collect::<HashMap<_, _>>();
+
+                            // disabled mods that could provide missing references
+                            let mut candidate_mods = Vec::new();
+                            if enabled_lints.contains(&LintId::UNRESOLVED_REFERENCES) {
+                                self.state.mod_data.for_each_mod(
+                                    &self.state.mod_data.active_profile,
+                                    |mc| {
+                                        if !mods.contains(&mc.spec)
+                                            && !candidate_mods.contains(&mc.spec)
+                                        {
+                                            candidate_mods.push(mc.spec.clone());
+                                            if !mc.disabled_paks.is_empty() {
+                                                disabled_paks.insert(
+                                                    mc.spec.clone(),
+                                                    mc.disabled_paks.clone(),
+                                                );
+                                            }
+                                        }
+                                    },
+                                );
+                            }
+
+                            let mod_infos = mods
+                                .iter()
+                                .filter_map(|spec| {
+                                    Some((spec.clone(), self.state.store.get_mod_info(spec)?))
+                                })
+                                .collect();
 
                             self.lint_report = None;
                             self.lint_rid = Some(message::LintMods::send(
                                 &mut self.request_counter,
                                 self.state.store.clone(),
-                                mods,
-                                BTreeSet::from_iter(
-                                    lint_options
-                                        .into_iter()
-                                        .filter_map(|(lint, enabled)| enabled.then_some(lint)),
-                                ),
+                                mod_configs,
+                                candidate_mods,
+                                disabled_paks,
+                                mod_infos,
+                                enabled_lints,
+                                self.state.mod_data.get_active_profile().lint_suppressions.clone(),
                                 self.state.config.drg_pak_path.clone(),
+                                self.state.dirs.cache_dir.clone(),
                                 self.tx.clone(),
                                 ctx.clone(),
                             ));
 
-                            self.lint_report_window = Some(WindowLintReport);
+                            self.lint_report_window = Some(WindowLintReport {
+                                profile: self.state.mod_data.active_profile.clone(),
+                                use_fixed: false,
+                            });
                         }
                     });
                 });
@@ -1488,296 +1720,376 @@ impl App {
         }
     }
 
-    fn show_lint_report(&mut self, ctx: &egui::Context) {
-        if self.lint_report_window.is_some() {
+    fn show_integration_report(&mut self, ctx: &egui::Context) {
+        if let Some(report) = &self.integration_report {
             let mut open = true;
 
-            egui::Window::new("Lint Results")
+            egui::Window::new("Integration Report")
                 .open(&mut open)
                 .resizable(true)
                 .show(ctx, |ui| {
-                    if let Some(report) = &self.lint_report {
-                        let scroll_height =
-                            (ui.available_height() - 30.0).clamp(0.0, f32::INFINITY);
-                        egui::ScrollArea::vertical()
-                            .max_height(scroll_height)
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        if !report.datatable_conflicts.is_empty() {
+                            CollapsingHeader::new(
+                                RichText::new("\u{26A0} DataTable row conflicts")
+                                    .color(colors::AMBER),
+                            )
+                            .default_open(true)
                             .show(ui, |ui| {
-                                if let Some(conflicting_mods) = &report.conflicting_mods {
-                                    if !conflicting_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new("\u{26A0} Mods(s) with conflicting asset modifications detected")
-                                                .color(colors::AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            conflicting_mods.iter().for_each(|(path, mods)| {
-                                                CollapsingHeader::new(
-                                                    RichText::new(format!(
-                                                        "\u{26A0} Conflicting modification of asset `{}`",
-                                                        path
-                                                    ))
-                                                    .color(colors::AMBER),
-                                                )
-                                                .show(
-                                                    ui,
-                                                    |ui| {
-                                                        mods.iter().for_each(|mod_spec| {
-                                                            ui.label(&mod_spec.url);
-                                                        });
-                                                    },
-                                                );
-                                            });
-                                        });
-                                    }
-                                }
-
-                                if let Some(asset_register_bin_mods) = &report.asset_register_bin_mods {
-                                    if !asset_register_bin_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new("\u{2139} Mod(s) with `AssetRegistry.bin` included detected")
-                                                .color(Color32::LIGHT_BLUE),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            asset_register_bin_mods.iter().for_each(
-                                                |(r#mod, paths)| {
-                                                    CollapsingHeader::new(
-                                                        RichText::new(format!(
-                                                        "\u{2139} {} includes one or more `AssetRegistry.bin`",
-                                                        r#mod.url
-                                                    ))
-                                                        .color(Color32::LIGHT_BLUE),
-                                                    )
-                                                    .show(ui, |ui| {
-                                                        paths.iter().for_each(|path| {
-                                                            ui.label(path);
-                                                        });
-                                                    });
-                                                },
-                                            );
-                                        });
-                                    }
-                                }
-
-                                if let Some(shader_file_mods) = &report.shader_file_mods {
-                                    if !shader_file_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "\u{26A0} Mods(s) with shader files included detected",
-                                            )
+                                for conflict in &report.datatable_conflicts {
+                                    ui.label(
+                                        RichText::new(format!("\u{26A0} {conflict}"))
                                             .color(colors::AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            shader_file_mods.iter().for_each(
-                                                |(r#mod, shader_files)| {
-                                                    CollapsingHeader::new(
-                                                        RichText::new(format!(
-                                                            "\u{26A0} {} includes one or more shader files",
-                                                            r#mod.url
-                                                        ))
-                                                        .color(colors::AMBER),
-                                                    )
-                                                    .show(ui, |ui| {
-                                                        shader_files.iter().for_each(|shader_file| {
-                                                            ui.label(shader_file);
-                                                        });
-                                                    });
-                                                },
-                                            );
-                                        });
-                                    }
+                                    );
                                 }
-
-                                if let Some(outdated_pak_version_mods) = &report.outdated_pak_version_mods {
-                                    if !outdated_pak_version_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "\u{26A0} Mod(s) with outdated pak version detected",
-                                            )
+                            });
+                        }
+                        if !report.ineffective_patches.is_empty() {
+                            CollapsingHeader::new(
+                                RichText::new("\u{26A0} Patches that changed nothing")
+                                    .color(colors::AMBER),
+                            )
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for patch in &report.ineffective_patches {
+                                    ui.label(
+                                        RichText::new(format!("\u{26A0} {patch}"))
                                             .color(colors::AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            outdated_pak_version_mods.iter().for_each(
-                                                |(r#mod, version)| {
-                                                    ui.label(
-                                                        RichText::new(format!(
-                                                            "\u{26A0} {} includes outdated pak version {}",
-                                                            r#mod.url, version
-                                                        ))
-                                                        .color(colors::AMBER),
-                                                    );
-                                                },
-                                            );
-                                        });
-                                    }
+                                    );
                                 }
+                            });
+                        }
+                    });
+                });
 
-                                if let Some(empty_archive_mods) = &report.empty_archive_mods {
-                                    if !empty_archive_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "\u{26A0} Mod(s) with empty archives detected",
-                                            )
-                                            .color(colors::AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            empty_archive_mods.iter().for_each(|r#mod| {
-                                                ui.label(
-                                                    RichText::new(format!(
-                                                        "\u{26A0} {} contains an empty archive",
-                                                        r#mod.url
-                                                    ))
-                                                    .color(colors::AMBER),
-                                                );
-                                            });
-                                        });
-                                    }
-                                }
+            if !open {
+                self.integration_report = None;
+            }
+        }
+    }
 
-                                if let Some(archive_with_only_non_pak_files_mods) = &report.archive_with_only_non_pak_files_mods {
-                                    if !archive_with_only_non_pak_files_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "\u{26A0} Mod(s) with only non-`.pak` files detected",
-                                            )
-                                            .color(colors::AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            archive_with_only_non_pak_files_mods.iter().for_each(|r#mod| {
-                                                ui.label(
-                                                    RichText::new(format!(
-                                                        "\u{26A0} {} contains only non-`.pak` files, perhaps the author forgot to pack it?",
-                                                        r#mod.url
-                                                    ))
-                                                    .color(colors::AMBER),
-                                                );
-                                            });
-                                        });
-                                    }
-                                }
+    fn show_modio_ini(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.modio_ini_window else {
+            return;
+        };
+        let mut open = true;
+        let mut close = false;
 
-                                if let Some(archive_with_multiple_paks_mods) = &report.archive_with_multiple_paks_mods {
-                                    if !archive_with_multiple_paks_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "\u{26A0} Mod(s) with multiple `.pak`s detected",
-                                            )
-                                            .color(colors::AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            archive_with_multiple_paks_mods.iter().for_each(|r#mod| {
-                                                ui.label(RichText::new(format!(
-                                                    "\u{26A0} {} contains multiple `.pak`s, only the first encountered `.pak` will be loaded",
-                                                    r#mod.url
-                                                ))
-                                                .color(colors::AMBER));
-                                            });
-                                        });
-                                    }
-                                }
+        egui::Window::new("Official mod.io Integration")
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                if let Some((update, diff)) = &window.update {
+                    ui.label(format!(
+                        "Mods installed through mod.io will be disabled and mods of the active profile enabled in {}:",
+                        update.path.display()
+                    ));
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            let added = if ui.visuals().dark_mode {
+                                Color32::LIGHT_GREEN
+                            } else {
+                                Color32::DARK_GREEN
+                            };
+                            for line in diff {
+                                let text = match line {
+                                    DiffLine::Unchanged(l) => RichText::new(format!("  {l}")).weak(),
+                                    DiffLine::Added(l) => RichText::new(format!("+ {l}")).color(added),
+                                    DiffLine::Removed(l) => RichText::new(format!("- {l}"))
+                                        .color(ui.visuals().error_fg_color),
+                                };
+                                ui.label(text.monospace());
+                            }
+                        });
+                    ui.horizontal(|ui| {
+                        if ui.button("Apply").clicked() {
+                            self.last_action = Some(match update.apply() {
+                                Ok(backup) => LastAction::success(format!(
+                                    "mod.io config updated, backup saved to {}",
+                                    backup.display()
+                                )),
+                                Err(e) => LastAction::failure(format!(
+                                    "Failed to update mod.io config: {e}"
+                                )),
+                            });
+                            close = true;
+                        }
+                        if ui.button("Skip").clicked() {
+                            close = true;
+                        }
+                    });
+                    ui.separator();
+                }
 
-                                if let Some(non_asset_file_mods) = &report.non_asset_file_mods {
-                                    if !non_asset_file_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "\u{26A0} Mod(s) with non-asset files detected",
-                                            )
-                                            .color(colors::AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            non_asset_file_mods.iter().for_each(|(r#mod, files)| {
-                                                CollapsingHeader::new(
-                                                    RichText::new(format!(
-                                                        "\u{26A0} {} includes non-asset files",
-                                                        r#mod.url
-                                                    ))
-                                                    .color(colors::AMBER),
-                                                )
-                                                .show(ui, |ui| {
-                                                    files.iter().for_each(|file| {
-                                                        ui.label(file);
-                                                    });
-                                                });
-                                            });
-                                        });
-                                    }
+                CollapsingHeader::new("Backups")
+                    .default_open(window.update.is_none())
+                    .show(ui, |ui| {
+                        if window.backups.is_empty() {
+                            ui.label("No backups");
+                        }
+                        let mut restored = false;
+                        for backup in &window.backups {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    backup
+                                        .file_name()
+                                        .unwrap_or_default()
+                                        .to_string_lossy()
+                                        .to_string(),
+                                );
+                                if ui.button("Restore").clicked() {
+                                    self.last_action = Some(match restore_modio_ini(&window.pak_path, backup) {
+                                        Ok(()) => LastAction::success(format!(
+                                            "mod.io config restored from {}",
+                                            backup.display()
+                                        )),
+                                        Err(e) => LastAction::failure(format!(
+                                            "Failed to restore mod.io config: {e}"
+                                        )),
+                                    });
+                                    restored = true;
                                 }
+                            });
+                        }
+                        if restored {
+                            // restoring backs up the current config first
+                            window.update = None;
+                            window.backups = modio_ini_backups(&window.pak_path).unwrap_or_default();
+                        }
+                    });
+            });
 
-                                if let Some(split_asset_pairs_mods) = &report.split_asset_pairs_mods {
-                                    if !split_asset_pairs_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "\u{26A0} Mod(s) with split {uexp, uasset} pairs detected",
-                                            )
-                                            .color(colors::AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            split_asset_pairs_mods.iter().for_each(|(r#mod, files)| {
-                                                CollapsingHeader::new(
-                                                    RichText::new(format!(
-                                                        "\u{26A0} {} includes split {{uexp, uasset}} pairs",
-                                                        r#mod.url
-                                                    ))
-                                                    .color(colors::AMBER),
-                                                )
-                                                .show(ui, |ui| {
-                                                    files.iter().for_each(|(file, kind)| {
-                                                        match kind {
-                                                            SplitAssetPair::MissingUasset => {
-                                                                ui.label(format!("`{file}` missing matching .uasset file"));
-                                                            },
-                                                            SplitAssetPair::MissingUexp => {
-                                                                ui.label(format!("`{file}` missing matching .uexp file"));
-                                                            }
+        if !open || close {
+            self.modio_ini_window = None;
+        }
+    }
+
+    fn show_lint_report(&mut self, ctx: &egui::Context) {
+        if let Some(window) = &mut self.lint_report_window {
+            let mut open = true;
+            let mut fix = false;
+            let mut suppress = None;
+            let mut unsuppress = None;
+            let mut save_baseline = false;
+            let mut clear_baseline = false;
+            let mut export = false;
+
+            let (suppressions, baseline) = self
+                .state
+                .mod_data
+                .profiles
+                .get(&window.profile)
+                .map(|p| (p.lint_suppressions.clone(), p.lint_baseline.clone()))
+                .unwrap_or_default();
+
+            egui::Window::new("Lint Results")
+                .open(&mut open)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    if let Some(report) = &self.lint_report {
+                        let scroll_height =
+                            (ui.available_height() - 30.0).clamp(0.0, f32::INFINITY);
+                        egui::ScrollArea::vertical()
+                            .max_height(scroll_height)
+                            .show(ui, |ui| {
+                                for lint in report.lints.iter().filter_map(|id| LintDefinition::get(*id)) {
+                                    let findings = report.findings_for(lint.id).collect::<Vec<_>>();
+                                    if findings.is_empty() {
+                                        continue;
+                                    }
+                                    let (icon, color) = match lint.severity {
+                                        Severity::Info => ("\u{2139}", Color32::LIGHT_BLUE),
+                                        Severity::Warning => ("\u{26A0}", colors::AMBER),
+                                        Severity::Error => ("\u{26D4}", ui.visuals().error_fg_color),
+                                    };
+                                    CollapsingHeader::new(
+                                        RichText::new(format!("{icon} {} ({})", lint.title, findings.len()))
+                                            .color(color),
+                                    )
+                                    .id_source(lint.id)
+                                    .default_open(true)
+                                    .show(ui, |ui| {
+                                        ui.label(RichText::new(lint.description).weak());
+                                        for finding in findings {
+                                            let text = if finding.is_new(baseline.as_ref()) {
+                                                RichText::new(format!("[new] {}", finding.message)).color(color).strong()
+                                            } else {
+                                                RichText::new(&finding.message).color(color)
+                                            };
+                                            let res = if finding.mods.len() > 1 {
+                                                CollapsingHeader::new(text)
+                                                    .show(ui, |ui| {
+                                                        for mod_spec in &finding.mods {
+                                                            ui.label(&mod_spec.url);
                                                         }
-                                                    });
-                                                });
+                                                    })
+                                                    .header_response
+                                            } else {
+                                                ui.add(Label::new(text).sense(egui::Sense::click()))
+                                            };
+                                            res.context_menu(|ui| {
+                                                if ui.button("Suppress this finding").clicked() {
+                                                    suppress = Some(LintSuppression::for_finding(finding));
+                                                    ui.close_menu();
+                                                }
+                                                let mods = finding.mods.iter().map(|m| m.url.as_str()).collect::<Vec<_>>().join(", ");
+                                                if finding.path.is_some() && ui.button(format!("Suppress this lint for {mods}")).clicked() {
+                                                    suppress = Some(LintSuppression::for_lint(finding));
+                                                    ui.close_menu();
+                                                }
                                             });
-                                        });
-                                    }
+                                        }
+                                    });
                                 }
 
-                                if let Some(unmodified_game_assets_mods) = &report.unmodified_game_assets_mods {
-                                    if !unmodified_game_assets_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "\u{26A0} Mod(s) with unmodified game assets detected",
-                                            )
-                                            .color(colors::AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            unmodified_game_assets_mods.iter().for_each(|(r#mod, files)| {
-                                                CollapsingHeader::new(
-                                                    RichText::new(format!(
-                                                        "\u{26A0} {} includes unmodified game assets",
-                                                        r#mod.url
-                                                    ))
-                                                    .color(colors::AMBER),
-                                                )
-                                                .show(ui, |ui| {
-                                                    files.iter().for_each(|file| {
-                                                        ui.label(file);
-                                                    });
+                                if !suppressions.is_empty() {
+                                    CollapsingHeader::new(format!(
+                                        "Suppressions ({} findings hidden)",
+                                        report.suppressed.len()
+                                    ))
+                                    .id_source("lint_suppressions")
+                                    .show(ui, |ui| {
+                                        for suppression in &suppressions {
+                                            ui.horizontal(|ui| {
+                                                if ui
+                                                    .button("Remove")
+                                                    .on_hover_text("Takes effect the next time the report is generated")
+                                                    .clicked()
+                                                {
+                                                    unsuppress = Some(suppression.clone());
+                                                }
+                                                let title = LintId::from_name(&suppression.lint)
+                                                    .and_then(LintDefinition::get)
+                                                    .map_or(suppression.lint.as_str(), |lint| lint.title);
+                                                let mods = suppression.mods.iter().map(|m| m.url.as_str()).collect::<Vec<_>>().join(", ");
+                                                ui.label(match &suppression.path {
+                                                    _ if suppression.all_paths => format!("{title}: all findings about {mods}"),
+                                                    Some(path) => format!("{title}: `{path}` in {mods}"),
+                                                    None => format!("{title}: {mods}"),
                                                 });
                                             });
-                                        });
-                                    }
+                                        }
+                                    });
                                 }
                             });
+
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button("Save as baseline")
+                                .on_hover_text("Highlight findings that aren't in this report as new in later reports of the profile")
+                                .clicked()
+                            {
+                                save_baseline = true;
+                            }
+                            if baseline.is_some() && ui.button("Clear baseline").clicked() {
+                                clear_baseline = true;
+                            }
+
+                            ui.separator();
+                            if ui
+                                .button("Export...")
+                                .on_hover_text("Save the report as Markdown or as an HTML page")
+                                .clicked()
+                            {
+                                export = true;
+                            }
+                            if ui.button("Copy as Markdown").clicked() {
+                                ui.output_mut(|o| o.copied_text = report.to_markdown());
+                            }
+
+                            if report.findings.iter().any(|f| {
+                                LintDefinition::get(f.lint).is_some_and(|lint| lint.fixable)
+                            }) {
+                                ui.separator();
+                                if ui
+                                    .button("Write cleaned paks")
+                                    .on_hover_text("Write copies of the mod paks without the files reported by fixable lints")
+                                    .clicked()
+                                {
+                                    fix = true;
+                                }
+                                ui.checkbox(&mut window.use_fixed, "Use cleaned paks in profile");
+                            }
+                        });
                     } else {
                         ui.spinner();
                         ui.label("Lint report generating...");
                     }
                 });
 
+            if export
+                && let Some(report) = &self.lint_report
+                && let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Markdown", &["md"])
+                    .add_filter("HTML", &["html"])
+                    .set_file_name("lint_report.md")
+                    .save_file()
+            {
+                let is_html = path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"));
+                let contents = if is_html {
+                    report.to_html()
+                } else {
+                    report.to_markdown()
+                };
+                self.last_action = Some(match fs_err::write(&path, contents) {
+                    Ok(()) => LastAction::success(format!("Lint report exported to {}", path.display())),
+                    Err(e) => LastAction::failure(format!("Failed to export lint report: {e}")),
+                });
+            }
+
+            let profile_changed =
+                suppress.is_some() || unsuppress.is_some() || save_baseline || clear_baseline;
+            if profile_changed
+                && let Some(profile) = self.state.mod_data.profiles.get_mut(&window.profile)
+            {
+                if let Some(suppression) = suppress {
+                    if let Some(report) = &mut self.lint_report {
+                        report.suppress(&suppression);
+                    }
+                    profile.lint_suppressions.insert(suppression);
+                }
+                if let Some(suppression) = unsuppress {
+                    profile.lint_suppressions.remove(&suppression);
+                }
+                if save_baseline && let Some(report) = &self.lint_report {
+                    profile.lint_baseline = Some(report.baseline());
+                }
+                if clear_baseline {
+                    profile.lint_baseline = None;
+                }
+                self.state.mod_data.save().unwrap();
+            }
+
+            if fix && let Some(report) = &self.lint_report {
+                self.last_action = Some(match fix_mods(report, &self.state.store) {
+                    Ok(fixed_mods) => {
+                        let mod_data = &mut self.state.mod_data;
+                        if window.use_fixed
+                            && !fixed_mods.is_empty()
+                            && mod_data.profiles.contains_key(&window.profile)
+                        {
+                            mod_data.for_each_mod_mut(&window.profile, |mc| {
+                                if let Some(fixed) =
+                                    fixed_mods.iter().find(|f| f.mod_spec == mc.spec)
+                                {
+                                    mc.use_fixed_pak(fixed.path.clone(), &fixed.source);
+                                }
+                            });
+                            mod_data.save().unwrap();
+                        }
+                        let removed = fixed_mods.iter().map(|f| f.removed.len()).sum::<usize>();
+                        LastAction::success(format!(
+                            "Removed {removed} files from {} mods",
+                            fixed_mods.len()
+                        ))
+                    }
+                    Err(e) => LastAction::failure(format!("Failed to write cleaned paks: {e}")),
+                });
+            }
+
             if !open {
                 self.lint_report_window = None;
                 self.lint_rid = None;
@@ -2137,7 +2449,12 @@ impl WindowSettings {
     }
 }
 
-struct WindowLintReport;
+struct WindowLintReport {
+    /// Profile that was linted.
+    profile: String,
+    /// Whether to replace the mods of the profile with their cleaned paks.
+    use_fixed: bool,
+}
 
 struct WindowLintsToggle;
 
@@ -2153,6 +2470,25 @@ struct WindowDetailedModInfo {
     info: ModInfo,
 }
 
+struct WindowModioIni {
+    pak_path: PathBuf,
+    update: Option<(ModioIniUpdate, Vec<DiffLine>)>,
+    backups: Vec<PathBuf>,
+}
+
+impl WindowModioIni {
+    fn new(pak_path: PathBuf, update: Option<ModioIniUpdate>) -> Result<Self> {
+        Ok(Self {
+            backups: modio_ini_backups(&pak_path)?,
+            pak_path,
+            update: update.map(|update| {
+                let diff = update.diff();
+                (update, diff)
+            }),
+        })
+    }
+}
+
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // do some init things that depend on ctx so cannot be done earlier
@@ -2185,6 +2521,8 @@ impl eframe::App for App {
         self.show_notes(ctx);
         self.show_lints_toggle(ctx);
         self.show_lint_report(ctx);
+        self.show_integration_report(ctx);
+        self.show_modio_ini(ctx);
 
         let modio_ids = self
             .detailed_mod_info_windows
@@ -2238,7 +2576,6 @@ impl eframe::App for App {
 
                             if button.clicked() {
                                 let mut mod_configs = Vec::new();
-                                let mut mods = Vec::new();
                                 let active_profile = self.state.mod_data.active_profile.clone();
 
                                 self.state
@@ -2249,16 +2586,19 @@ impl eframe::App for App {
 
                                 mod_configs.sort_by_key(|k| -k.priority);
 
-                                for config in mod_configs {
-                                    mods.push(config.spec.clone());
-                                }
-
                                 self.last_action = None;
+                                self.integration_report = None;
                                 self.integrate_rid = Some(message::Integrate::send(
                                     &mut self.request_counter,
                                     self.state.store.clone(),
-                                    mods,
+                                    mod_configs,
                                     self.state.config.drg_pak_path.as_ref().unwrap().clone(),
+                                    IntegrationOptions {
+                                        disabled_patches: self.state.mod_data.profiles[&active_profile]
+                                            .disabled_patches
+                                            .clone(),
+                                        ..self.state.config.integration_options()
+                                    },
                                     self.tx.clone(),
                                     ctx.clone(),
                                 ));
@@ -2293,14 +2633,26 @@ impl eframe::App for App {
                                     );
 
                                     debug!("uninstalling mods: pak_path = {}", pak_path.display());
-                                    self.last_action = Some(match uninstall(pak_path, mods) {
-                                        Ok(()) => LastAction::success(
-                                            "DLL hook and mods removed".to_string(),
-                                        ),
-                                        Err(e) => LastAction::failure(format!(
+                                    let uninstalled = uninstall(pak_path);
+                                    let modio_ini = plan_modio_ini_update(pak_path, &mods).and_then(|update| {
+                                        update
+                                            .map(|update| WindowModioIni::new(pak_path.clone(), Some(update)))
+                                            .transpose()
+                                    });
+                                    self.last_action = Some(match (uninstalled, modio_ini) {
+                                        (Err(e), _) => LastAction::failure(format!(
                                             "Failed to uninstall mods: {e}"
                                         )),
-                                    })
+                                        (Ok(()), Ok(window)) => {
+                                            self.modio_ini_window = window;
+                                            LastAction::success(
+                                                "DLL hook and mods removed".to_string(),
+                                            )
+                                        }
+                                        (Ok(()), Err(e)) => LastAction::failure(format!(
+                                            "DLL hook and mods removed, but the mod.io config can't be updated: {e}"
+                                        )),
+                                    });
                                 }
                             }
                         });
@@ -1,24 +1,45 @@
-use fs::OpenOptions;
-use std::collections::{HashMap, HashSet};
+mod datatable;
+#[cfg(feature = "hook")]
+mod hook_dll;
+mod modio_ini;
+mod selftest;
+mod target;
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::ffi::{OsStr, OsString};
 use std::io::{self, BufReader, BufWriter, Cursor, ErrorKind, Read, Seek};
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use fs_err as fs;
+use indexmap::{IndexMap, IndexSet};
 use mint_lib::DRGInstallation;
+use rayon::prelude::*;
 use repak::PakWriter;
-use tracing::info;
+use strum::IntoEnumIterator;
+use tracing::{info, warn};
 use uasset_utils::splice::{
     extract_tracked_statements, inject_tracked_statements, walk, AssetVersion, TrackedStatement,
 };
 
-use crate::get_pak_from_data;
-use crate::providers::ModInfo;
+pub use self::datatable::DataTableConflict;
+use self::datatable::DataTableVariant;
+pub use self::modio_ini::{
+    modio_ini_backups, plan_modio_ini_update, restore_modio_ini, DiffLine, ModioIniUpdate,
+};
+pub use self::selftest::{selftest, SelftestCheck};
+pub use self::target::{BuildTarget, TargetOutput, TargetPreset};
+
+use crate::get_paks_from_data;
+use crate::mod_lints::{resolve_path, UnresolvablePath};
+use crate::providers::{ApprovalStatus, ModInfo};
+use crate::state::ModConfig;
 
 use unreal_asset::{
-    exports::ExportBaseTrait,
-    flags::EObjectFlags,
+    exports::{Export, ExportBaseTrait},
+    flags::{EObjectFlags, EPropertyFlags},
+    fproperty::{FArrayProperty, FGenericProperty, FProperty},
     kismet::{
         EExprToken, ExByteConst, ExCallMath, ExLet, ExLetObj, ExLocalVariable, ExRotationConst,
         ExSelf, ExSoftObjectConst, ExStringConst, ExVectorConst, FieldPath, KismetPropertyPointer,
@@ -26,7 +47,8 @@ use unreal_asset::{
     kismet::{ExFalse, KismetExpression},
     properties::object_property::TopLevelAssetPath,
     properties::{
-        int_property::BoolProperty,
+        array_property::ArrayProperty,
+        int_property::{BoolProperty, IntProperty},
         object_property::{SoftObjectPath, SoftObjectProperty},
         str_property::StrProperty,
         struct_property::StructProperty,
@@ -38,14 +60,10 @@ use unreal_asset::{
     Asset,
 };
 
-/// Why does the uninstall function require a list of Modio mod IDs?
-/// Glad you ask. The official integration enables *every mod the user has installed* once it gets
-/// re-enabled. We do the user a favor and collect all the installed mods and explicitly add them
-/// back to the config so they will be disabled when the game is launched again. Since we have
-/// Modio IDs anyway, with just a little more effort we can make the 'uninstall' button work as an
-/// 'install' button for the official integration. Best anti-feature ever.
+/// Removes the mod bundle and hook from the game installation. The config of the official mod.io
+/// integration is left alone, see [`plan_modio_ini_update`] for that.
 #[tracing::instrument(level = "debug", skip(path_pak))]
-pub fn uninstall<P: AsRef<Path>>(path_pak: P, modio_mods: HashSet<u32>) -> Result<()> {
+pub fn uninstall<P: AsRef<Path>>(path_pak: P) -> Result<()> {
     let installation = DRGInstallation::from_pak_path(path_pak)?;
     let path_mods_pak = installation.paks_path().join("mods_P.pak");
     match fs::remove_file(&path_mods_pak) {
@@ -59,87 +77,185 @@ pub fn uninstall<P: AsRef<Path>>(path_pak: P, modio_mods: HashSet<u32>) -> Resul
         let path_hook_dll = installation
             .binaries_directory()
             .join(installation.installation_type.hook_dll_name());
-        match fs::remove_file(&path_hook_dll) {
-            Ok(()) => Ok(()),
-            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
-            Err(e) => Err(e),
-        }
-        .with_context(|| format!("failed to remove {}", path_hook_dll.display()))?;
+        hook_dll::uninstall(&path_hook_dll).with_context(|| {
+            format!("failed to uninstall hook from {}", path_hook_dll.display())
+        })?;
     }
-    uninstall_modio(&installation, modio_mods).ok();
     Ok(())
 }
 
-#[tracing::instrument(level = "debug")]
-fn uninstall_modio(installation: &DRGInstallation, modio_mods: HashSet<u32>) -> Result<()> {
-    #[derive(Debug, serde::Deserialize)]
-    struct ModioState {
-        #[serde(rename = "Mods")]
-        mods: Vec<ModioMod>,
+/// Pak version of the generated mod bundle.
+const BUNDLE_PAK_VERSION: repak::Version = repak::Version::V11;
+
+/// Blueprints mint ships in the bundle to load mods in game.
+const INTEGRATION_PAK: &[u8] = include_bytes!("../assets/integration.pak");
+/// Directory of the assets mint adds to the game. They replace files mods have at the same paths,
+/// other files mods have there are kept.
+pub(crate) const INTEGRATION_DIR: &str = "FSD/Content/_AssemblyStorm/ModIntegration";
+const SPAWN_MODS_PATH: &str = "FSD/Content/_AssemblyStorm/ModIntegration/MI_SpawnMods";
+const MOD_STRUCT_PATH: &str = "FSD/Content/_AssemblyStorm/ModIntegration/MI_Mod";
+
+/// Compression applied to files written to the mod bundle.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    clap::ValueEnum,
+)]
+pub enum BundleCompression {
+    /// Store files uncompressed. Fastest to build and load, useful for debugging.
+    None,
+    /// Zlib compression, readable by every pak version.
+    #[default]
+    Zlib,
+    /// Oodle compression, requires the `oodle` feature.
+    #[cfg_attr(not(feature = "oodle"), value(skip))]
+    Oodle,
+}
+
+impl BundleCompression {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BundleCompression::None => "None",
+            BundleCompression::Zlib => "Zlib",
+            BundleCompression::Oodle => "Oodle",
+        }
     }
-    #[derive(Debug, serde::Deserialize)]
-    struct ModioMod {
-        #[serde(rename = "ID")]
-        id: u32,
+
+    fn methods(self) -> Result<Vec<repak::Compression>> {
+        Ok(match self {
+            BundleCompression::None => vec![],
+            BundleCompression::Zlib => vec![repak::Compression::Zlib],
+            #[cfg(feature = "oodle")]
+            BundleCompression::Oodle => vec![repak::Compression::Oodle],
+            #[cfg(not(feature = "oodle"))]
+            BundleCompression::Oodle => {
+                bail!("Oodle compression is not available in this build of mint")
+            }
+        })
     }
-    let Some(modio_dir) = installation.modio_directory() else {
-        return Ok(());
-    };
-    let modio_state: ModioState = serde_json::from_reader(std::io::BufReader::new(
-        fs::File::open(modio_dir.join("metadata/state.json"))?,
-    ))?;
-    let config_path = installation
-        .root
-        .join("Saved/Config/WindowsNoEditor/GameUserSettings.ini");
-    let mut config = ini::Ini::load_from_file(&config_path)?;
-
-    let ignore_keys = HashSet::from(["CurrentModioUserId"]);
-
-    config
-        .entry(Some("/Script/FSD.UserGeneratedContent".to_string()))
-        .or_insert_with(Default::default);
-    if let Some(ugc_section) = config.section_mut(Some("/Script/FSD.UserGeneratedContent")) {
-        let local_mods = installation
-            .root
-            .join("Mods")
-            .read_dir()?
-            .map(|f| {
-                let f = f?;
-                Ok((!f.path().is_file())
-                    .then_some(f.file_name().to_string_lossy().to_string().to_string()))
-            })
-            .collect::<Result<Vec<Option<String>>>>()?;
-        let to_remove = HashSet::from_iter(ugc_section.iter().map(|(k, _)| k))
-            .difference(&ignore_keys)
-            .map(|&k| k.to_owned())
-            .collect::<Vec<String>>();
-        for r in to_remove {
-            let _ = ugc_section.remove_all(r);
-        }
-        for m in modio_state.mods {
-            ugc_section.insert(
-                m.id.to_string(),
-                if modio_mods.contains(&m.id) {
-                    "True"
-                } else {
-                    "False"
-                },
+
+    /// Checks whether a bundle written with this compression can be read by a game whose main
+    /// pak has version `game_version`. Every compression method is supported by pak versions
+    /// that can read the bundle at all, so only the version itself needs to be compared.
+    pub fn check_game_pak_version(self, game_version: repak::Version) -> Result<()> {
+        if game_version < BUNDLE_PAK_VERSION {
+            bail!(
+                "game pak is version {game_version:?} but mod bundles are written as {BUNDLE_PAK_VERSION:?}"
             );
         }
-        for m in local_mods.into_iter().flatten() {
-            ugc_section.insert(m, "False");
+        Ok(())
+    }
+}
+
+/// Options controlling how the mod bundle is built.
+#[derive(Debug, Default, Clone)]
+pub struct IntegrationOptions {
+    pub compression: BundleCompression,
+    /// Merge rows of DataTables overridden by several mods instead of only using the version of
+    /// the mod with the highest priority.
+    pub merge_datatables: bool,
+    /// Use the newest version of unpinned mod.io mods named after the installed game version when
+    /// their tags show the latest version isn't made for it.
+    pub prefer_compatible_modfiles: bool,
+    /// IDs of [`IntegrationPatch`]es not to apply.
+    pub disabled_patches: BTreeSet<String>,
+    pub target: BuildTarget,
+}
+
+/// A change made to game assets so that mods get loaded and modded sessions behave like vanilla
+/// ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter)]
+pub enum IntegrationPatch {
+    SpawnMods,
+    ModdedServerCheck,
+}
+
+impl IntegrationPatch {
+    /// Stable identifier used to store whether the patch is enabled.
+    pub fn id(self) -> &'static str {
+        match self {
+            IntegrationPatch::SpawnMods => "spawn_mods",
+            IntegrationPatch::ModdedServerCheck => "modded_server_check",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            IntegrationPatch::SpawnMods => "Spawn mods",
+            IntegrationPatch::ModdedServerCheck => "Hide modded servers",
         }
-        ugc_section.insert("CheckGameversion", "False");
     }
 
-    config.write_to_file_opt(
-        config_path,
-        ini::WriteOption {
-            line_separator: ini::LineSeparator::CRLF,
-            ..Default::default()
-        },
-    )?;
-    Ok(())
+    pub fn description(self) -> &'static str {
+        match self {
+            IntegrationPatch::SpawnMods => "Hooks the player controller to spawn the mod integration actor, which loads mods' init actors and lists installed mods in game. Mods relying on init actors won't work without it",
+            IntegrationPatch::ModdedServerCheck => "Makes the game treat modded servers as unmodded so they can be joined and hosted like vanilla ones",
+        }
+    }
+
+    /// Paths of the game assets modified by the patch, relative to the game root and without
+    /// extension.
+    pub fn asset_paths(self) -> &'static [&'static str] {
+        match self {
+            IntegrationPatch::SpawnMods => &["FSD/Content/Game/BP_PlayerControllerBase"],
+            IntegrationPatch::ModdedServerCheck => &[
+                "FSD/Content/Game/BP_GameInstance",
+                "FSD/Content/Game/SpaceRig/BP_PlayerController_SpaceRig",
+                "FSD/Content/Game/StartMenu/Bp_StartMenu_PlayerController",
+                "FSD/Content/UI/Menu_DeepDives/ITM_DeepDives_Join",
+                "FSD/Content/UI/Menu_ServerList/_MENU_ServerList",
+                "FSD/Content/UI/Menu_ServerList/WND_JoiningModded",
+            ],
+        }
+    }
+
+    /// Applies the patch to `asset` and returns the number of changes made.
+    pub fn apply<R: Read + Seek>(self, asset: &mut Asset<R>) -> Result<usize> {
+        match self {
+            IntegrationPatch::SpawnMods => hook_pcb(asset),
+            IntegrationPatch::ModdedServerCheck => patch(asset),
+        }
+    }
+}
+
+/// An enabled patch that left one of its assets unchanged, usually because a game update changed
+/// the asset.
+#[derive(Debug, Clone)]
+pub struct IneffectivePatch {
+    pub patch: IntegrationPatch,
+    pub asset: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for IneffectivePatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "patch \"{}\" did not change {}: {}",
+            self.patch.name(),
+            self.asset,
+            self.reason
+        )
+    }
+}
+
+/// Things worth telling the user about a successful integration.
+#[derive(Debug, Default, Clone)]
+pub struct IntegrationReport {
+    pub datatable_conflicts: Vec<DataTableConflict>,
+    pub ineffective_patches: Vec<IneffectivePatch>,
+}
+
+impl IntegrationReport {
+    pub fn is_empty(&self) -> bool {
+        self.datatable_conflicts.is_empty() && self.ineffective_patches.is_empty()
+    }
 }
 
 #[derive(Debug)]
@@ -155,15 +271,95 @@ pub enum IntegrationErrKind {
     UnrealAsset(unreal_asset::Error),
 }
 
-pub fn integrate<P: AsRef<Path>>(
-    path_pak: P,
-    mods: Vec<(ModInfo, PathBuf)>,
-) -> Result<(), IntegrationErr> {
-    let installation = DRGInstallation::from_pak_path(&path_pak).map_err(|e| IntegrationErr {
-        mod_ctxt: None,
+/// Lowercase paths relative to the game root of the files mint adds to the bundle from its
+/// integration pak.
+pub(crate) fn integration_files() -> Result<HashSet<String>> {
+    let mut int_pak_reader = Cursor::new(INTEGRATION_PAK);
+    let int_pak = repak::PakBuilder::new().reader(&mut int_pak_reader)?;
+    Ok(int_pak
+        .files()
+        .into_iter()
+        .filter_map(|p| resolve_path(int_pak.mount_point(), &p).ok())
+        .map(|p| p.to_ascii_lowercase())
+        .collect())
+}
+
+/// Reads and decompresses every file of a mod that should end up in the bundle. Paks inside an
+/// archive are read in order of their path, skipping those disabled in the mod's config. Returned
+/// paths are relative to the game root with `/` as separator.
+fn read_mod_files(
+    mod_info: &ModInfo,
+    mod_config: &ModConfig,
+    path: &Path,
+) -> Result<Vec<(String, Vec<u8>)>, IntegrationErr> {
+    let raw_mod_file = fs::File::open(path).map_err(|e| IntegrationErr {
+        mod_ctxt: Some(mod_info.clone()),
+        kind: IntegrationErrKind::Generic(e.into()),
+    })?;
+    let paks = get_paks_from_data(Box::new(BufReader::new(raw_mod_file)), |name| {
+        !mod_config.disabled_paks.contains(name)
+    })
+    .map_err(|e| IntegrationErr {
+        mod_ctxt: Some(mod_info.clone()),
         kind: IntegrationErrKind::Generic(e),
     })?;
-    let path_mod_pak = installation.paks_path().join("mods_P.pak");
+
+    let mut files = vec![];
+    for (_, mut buf) in paks {
+        let pak = repak::PakBuilder::new()
+            .reader(&mut buf)
+            .map_err(|e| IntegrationErr {
+                mod_ctxt: Some(mod_info.clone()),
+                kind: IntegrationErrKind::Repak(e),
+            })?;
+
+        for p in pak.files() {
+            // resolved like the game does so paks mounted deeper than the game root work as well
+            let resolved = resolve_path(pak.mount_point(), &p).map_err(|e| IntegrationErr {
+                mod_ctxt: Some(mod_info.clone()),
+                kind: IntegrationErrKind::Generic(match e {
+                    UnresolvablePath::Absolute => anyhow!("`{p}` is an absolute path"),
+                    UnresolvablePath::OutsideGameRoot => {
+                        anyhow!("`{p}` points outside the game directory")
+                    }
+                }),
+            })?;
+            let new_path = Path::new(&resolved);
+
+            if let Some(filename) = new_path.file_name() {
+                if filename == "AssetRegistry.bin" {
+                    continue;
+                }
+                if new_path.extension().and_then(std::ffi::OsStr::to_str) == Some("ushaderbytecode")
+                {
+                    continue;
+                }
+            }
+
+            let file_data = pak.get(&p, &mut buf).map_err(|e| IntegrationErr {
+                mod_ctxt: Some(mod_info.clone()),
+                kind: IntegrationErrKind::Repak(e),
+            })?;
+            files.push((new_path.to_string_lossy().replace('\\', "/"), file_data));
+        }
+    }
+
+    Ok(files)
+}
+
+pub fn integrate<P: AsRef<Path>>(
+    path_pak: P,
+    mods: Vec<(ModInfo, ModConfig, PathBuf)>,
+    options: &IntegrationOptions,
+) -> Result<IntegrationReport, IntegrationErr> {
+    let layout = options
+        .target
+        .layout(path_pak.as_ref())
+        .map_err(|e| IntegrationErr {
+            mod_ctxt: None,
+            kind: IntegrationErrKind::Generic(e),
+        })?;
+    let path_mod_pak = layout.mod_pak.clone();
 
     let fsd_pak_file = fs::File::open(path_pak.as_ref()).map_err(|e| IntegrationErr {
         mod_ctxt: None,
@@ -177,6 +373,15 @@ pub fn integrate<P: AsRef<Path>>(
             kind: IntegrationErrKind::Repak(e),
         })?;
 
+    let compression_methods = options
+        .compression
+        .check_game_pak_version(fsd_pak.version())
+        .and_then(|()| options.compression.methods())
+        .map_err(|e| IntegrationErr {
+            mod_ctxt: None,
+            kind: IntegrationErrKind::Generic(e),
+        })?;
+
     #[derive(Debug, Default)]
     struct Dir<'a> {
         name: &'a OsStr,
@@ -208,10 +413,16 @@ pub fn integrate<P: AsRef<Path>>(
         uexp: Option<Vec<u8>>,
     }
     impl RawAsset {
-        fn parse(&self) -> Result<Asset<Cursor<&Vec<u8>>>> {
+        fn is_complete(&self) -> bool {
+            self.uasset.is_some() && self.uexp.is_some()
+        }
+        fn parse(&self) -> Result<Asset<Cursor<Vec<u8>>>> {
+            let (Some(uasset), Some(uexp)) = (&self.uasset, &self.uexp) else {
+                bail!("asset not found in game or mods");
+            };
             Ok(unreal_asset::Asset::new(
-                Cursor::new(self.uasset.as_ref().unwrap()),
-                Some(Cursor::new(self.uexp.as_ref().unwrap())),
+                Cursor::new(uasset.clone()),
+                Some(Cursor::new(uexp.clone())),
                 unreal_asset::engine_version::EngineVersion::VER_UE4_27,
                 None,
             )?)
@@ -234,11 +445,15 @@ pub fn integrate<P: AsRef<Path>>(
         normalized_path
     };
 
+    // entries written to the bundle, checked when reading it back
+    let written_entries = RefCell::new(vec![]);
+
     let write_file = |pak: &mut PakWriter<_>, data: &[u8], path: &str| -> Result<()> {
         let binding = normalize_path(path);
         let path = binding.to_str().unwrap().replace('\\', "/");
 
         pak.write_file(&path, data)?;
+        written_entries.borrow_mut().push(path);
 
         Ok(())
     };
@@ -269,22 +484,18 @@ pub fn integrate<P: AsRef<Path>>(
         )
     }
 
-    let pcb_path = "FSD/Content/Game/BP_PlayerControllerBase";
-    let patch_paths = [
-        "FSD/Content/Game/BP_GameInstance",
-        "FSD/Content/Game/SpaceRig/BP_PlayerController_SpaceRig",
-        "FSD/Content/Game/StartMenu/Bp_StartMenu_PlayerController",
-        "FSD/Content/UI/Menu_DeepDives/ITM_DeepDives_Join",
-        "FSD/Content/UI/Menu_ServerList/_MENU_ServerList",
-        "FSD/Content/UI/Menu_ServerList/WND_JoiningModded",
-    ];
+    let patch_paths = IntegrationPatch::iter()
+        .flat_map(|patch| patch.asset_paths().iter().copied())
+        .collect::<IndexSet<_>>();
 
-    let mut deferred_assets: HashMap<&str, RawAsset> = HashMap::from_iter(
-        [pcb_path]
-            .iter()
-            .chain(patch_paths.iter())
-            .map(|path| (*path, RawAsset::default())),
-    );
+    // assets touched by patches are collected from the game and mods and written at the end,
+    // patched if any of their patches is enabled
+    let mut deferred_assets: HashMap<&str, RawAsset> = patch_paths
+        .iter()
+        .map(|path| (*path, RawAsset::default()))
+        .collect();
+    // deferred assets overridden by a mod, written even if none of their patches is enabled
+    let mut modded_assets = HashSet::new();
 
     // collect assets from game pak file
     for (path, asset) in &mut deferred_assets {
@@ -309,157 +520,283 @@ pub fn integrate<P: AsRef<Path>>(
         })?;
     }
 
+    // The bundle is written next to its final location and only moved there once it has been
+    // read back successfully, so a failed integration leaves the previous bundle in place.
+    let tmp_mod_pak = tempfile::Builder::new()
+        .prefix("mods_P")
+        .suffix(".pak.tmp")
+        .tempfile_in(path_mod_pak.parent().unwrap())
+        .map_err(|e| IntegrationErr {
+            mod_ctxt: None,
+            kind: IntegrationErrKind::Generic(e.into()),
+        })?;
+    // TODO make the compression level configurable once repak can take one, it always compresses
+    // with the default level of each method
     let mut mod_pak = repak::PakBuilder::new()
-        .compression([repak::Compression::Zlib])
+        .compression(compression_methods)
         .writer(
-            BufWriter::new(
-                OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .truncate(true)
-                    .open(&path_mod_pak)
-                    .map_err(|e| IntegrationErr {
-                        mod_ctxt: None,
-                        kind: IntegrationErrKind::Generic(e.into()),
-                    })?,
-            ),
-            repak::Version::V11,
+            BufWriter::new(tmp_mod_pak.reopen().map_err(|e| IntegrationErr {
+                mod_ctxt: None,
+                kind: IntegrationErrKind::Generic(e.into()),
+            })?),
+            BUNDLE_PAK_VERSION,
             "../../../".to_string(),
             None,
         );
 
-    #[cfg(feature = "hook")]
-    {
-        let path_hook_dll = installation
-            .binaries_directory()
-            .join(installation.installation_type.hook_dll_name());
-        let hook_dll = include_bytes!(env!("CARGO_CDYLIB_FILE_HOOK_hook"));
-        if path_hook_dll
-            .metadata()
-            .map(|m| m.len() != hook_dll.len() as u64)
-            .unwrap_or(true)
-        {
-            fs::write(&path_hook_dll, hook_dll)
-                .with_context(|| format!("failed to write hook to {}", path_hook_dll.display()))
-                .map_err(|e| IntegrationErr {
-                    mod_ctxt: None,
-                    kind: IntegrationErrKind::Generic(e),
-                })?;
-        }
-    }
-
     let mut init_spacerig_assets = HashSet::new();
     let mut init_cave_assets = HashSet::new();
 
-    let mut added_paths = HashSet::new();
-
-    for (mod_info, path) in &mods {
-        let raw_mod_file = fs::File::open(path).map_err(|e| IntegrationErr {
-            mod_ctxt: Some(mod_info.clone()),
-            kind: IntegrationErrKind::Generic(e.into()),
-        })?;
-        let mut buf = get_pak_from_data(Box::new(BufReader::new(raw_mod_file))).map_err(|e| {
-            IntegrationErr {
-                mod_ctxt: Some(mod_info.clone()),
-                kind: IntegrationErrKind::Generic(e),
-            }
-        })?;
-        let pak = repak::PakBuilder::new()
-            .reader(&mut buf)
-            .map_err(|e| IntegrationErr {
-                mod_ctxt: Some(mod_info.clone()),
-                kind: IntegrationErrKind::Repak(e),
-            })?;
-
-        let mount = Path::new(pak.mount_point());
-
-        for p in pak.files() {
-            let j = mount.join(&p);
-            let new_path = j
-                .strip_prefix("../../../")
-                .context("prefix does not match")
-                .map_err(|e| IntegrationErr {
-                    mod_ctxt: Some(mod_info.clone()),
-                    kind: IntegrationErrKind::Generic(e),
-                })?;
-            let new_path_str = &new_path.to_string_lossy().replace('\\', "/");
-            let lowercase = new_path_str.to_ascii_lowercase();
-            if added_paths.contains(&lowercase) {
-                continue;
-            }
+    // files of mint's integration pak replace those mods ship at the same paths
+    let mut added_paths = integration_files().map_err(|e| IntegrationErr {
+        mod_ctxt: None,
+        kind: IntegrationErrKind::Generic(e),
+    })?;
 
-            if let Some(filename) = new_path.file_name() {
-                if filename == "AssetRegistry.bin" {
+    // DataTables overridden by mods keyed by lowercase path without extension, along with every
+    // variant in load order. Only collected if DataTable merging is enabled.
+    let mut datatables: IndexMap<String, (String, Vec<DataTableVariant>)> = IndexMap::new();
+
+    // Mods are read and decompressed in parallel, one chunk at a time to bound memory usage,
+    // then written sequentially in load order so the output stays deterministic.
+    for chunk in mods.chunks(rayon::current_num_threads().max(1)) {
+        let chunk_files = chunk
+            .par_iter()
+            .map(|(mod_info, mod_config, path)| {
+                read_mod_files(mod_info, mod_config, &mod_config.file_path(path))
+            })
+            .collect::<Vec<_>>();
+
+        for ((mod_info, _, _), files) in chunk.iter().zip(chunk_files) {
+            let files = files?;
+
+            let datatable_paths = if options.merge_datatables {
+                files
+                    .iter()
+                    .filter(|(_, data)| datatable::is_candidate(data))
+                    .filter_map(|(path, _)| path.strip_suffix(".uasset"))
+                    .map(str::to_ascii_lowercase)
+                    .collect::<HashSet<_>>()
+            } else {
+                HashSet::new()
+            };
+            let mut pending_datatables: IndexMap<String, (String, RawAsset)> = IndexMap::new();
+
+            for (new_path_str, file_data) in files {
+                let lowercase = new_path_str.to_ascii_lowercase();
+
+                let datatable_path = lowercase
+                    .strip_suffix(".uasset")
+                    .or_else(|| lowercase.strip_suffix(".uexp"))
+                    .filter(|path| datatable_paths.contains(*path))
+                    .filter(|path| {
+                        !added_paths.contains(&lowercase) || datatables.contains_key(*path)
+                    });
+                if let Some(datatable_path) = datatable_path {
+                    let (_, raw) = pending_datatables
+                        .entry(datatable_path.to_string())
+                        .or_insert_with(|| {
+                            (
+                                new_path_str[..datatable_path.len()].to_string(),
+                                RawAsset::default(),
+                            )
+                        });
+                    if lowercase.ends_with(".uasset") {
+                        raw.uasset = Some(file_data);
+                    } else {
+                        raw.uexp = Some(file_data);
+                    }
+                    added_paths.insert(lowercase);
                     continue;
                 }
-                if new_path.extension().and_then(std::ffi::OsStr::to_str) == Some("ushaderbytecode")
-                {
+
+                if added_paths.contains(&lowercase) {
                     continue;
                 }
-                let lower = filename.to_string_lossy().to_lowercase();
-                if lower == "initspacerig.uasset" {
-                    init_spacerig_assets.insert(format_soft_class(new_path));
+
+                let new_path = Path::new(&new_path_str);
+                if let Some(filename) = new_path.file_name() {
+                    let lower = filename.to_string_lossy().to_lowercase();
+                    if lower == "initspacerig.uasset" {
+                        init_spacerig_assets.insert(format_soft_class(new_path));
+                    }
+                    if lower == "initcave.uasset" {
+                        init_cave_assets.insert(format_soft_class(new_path));
+                    }
                 }
-                if lower == "initcave.uasset" {
-                    init_cave_assets.insert(format_soft_class(new_path));
+
+                let deferred = new_path_str
+                    .strip_suffix(".uasset")
+                    .map(|path| (path, true))
+                    .or_else(|| new_path_str.strip_suffix(".uexp").map(|path| (path, false)))
+                    .and_then(|(path, is_uasset)| Some((*patch_paths.get(path)?, is_uasset)));
+                if let Some((path, is_uasset)) = deferred {
+                    let raw = deferred_assets.get_mut(path).unwrap();
+                    if is_uasset {
+                        raw.uasset = Some(file_data);
+                    } else {
+                        raw.uexp = Some(file_data);
+                    }
+                    modded_assets.insert(path);
+                } else {
+                    write_file(&mut mod_pak, &file_data, &new_path_str).map_err(|e| {
+                        IntegrationErr {
+                            mod_ctxt: Some(mod_info.clone()),
+                            kind: IntegrationErrKind::Generic(e),
+                        }
+                    })?;
+                    added_paths.insert(lowercase);
                 }
             }
 
-            let file_data = pak.get(&p, &mut buf).map_err(|e| IntegrationErr {
-                mod_ctxt: Some(mod_info.clone()),
-                kind: IntegrationErrKind::Repak(e),
-            })?;
-            if let Some(raw) = new_path_str
-                .strip_suffix(".uasset")
-                .and_then(|path| deferred_assets.get_mut(path))
-            {
-                raw.uasset = Some(file_data);
-            } else if let Some(raw) = new_path_str
-                .strip_suffix(".uexp")
-                .and_then(|path| deferred_assets.get_mut(path))
-            {
-                raw.uexp = Some(file_data);
-            } else {
-                write_file(&mut mod_pak, &file_data, new_path_str).map_err(|e| IntegrationErr {
-                    mod_ctxt: Some(mod_info.clone()),
-                    kind: IntegrationErrKind::Generic(e),
-                })?;
-                added_paths.insert(lowercase);
+            for (lowercase, (path, raw)) in pending_datatables {
+                match raw {
+                    RawAsset {
+                        uasset: Some(uasset),
+                        uexp: Some(uexp),
+                    } => {
+                        datatables
+                            .entry(lowercase)
+                            .or_insert_with(|| (path, vec![]))
+                            .1
+                            .push(DataTableVariant {
+                                mod_name: mod_info.name.clone(),
+                                uasset,
+                                uexp,
+                            });
+                    }
+                    // not a complete asset so there is nothing to merge. It's dropped if a
+                    // higher priority mod's version is being merged, otherwise written as is
+                    RawAsset { .. } if datatables.contains_key(&lowercase) => {}
+                    RawAsset { uasset, uexp } => {
+                        for (data, ext) in [(uasset, "uasset"), (uexp, "uexp")] {
+                            if let Some(data) = data {
+                                write_file(&mut mod_pak, &data, &format!("{path}.{ext}")).map_err(
+                                    |e| IntegrationErr {
+                                        mod_ctxt: Some(mod_info.clone()),
+                                        kind: IntegrationErrKind::Generic(e),
+                                    },
+                                )?;
+                            }
+                        }
+                    }
+                }
             }
         }
     }
 
-    {
-        let mut pcb_asset = deferred_assets[&pcb_path]
-            .parse()
+    let mut report = IntegrationReport::default();
+
+    for (path, variants) in datatables.into_values() {
+        let merged = if variants.len() > 1 {
+            let mut get_original = |ext: &str| {
+                let path = format!("{}.{ext}", normalize_path(&path).to_string_lossy());
+                match fsd_pak.get(&path.replace('\\', "/"), &mut fsd_pak_reader) {
+                    Ok(file) => Ok(Some(file)),
+                    Err(repak::Error::MissingEntry(_)) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            };
+            let original = match (get_original("uasset"), get_original("uexp")) {
+                (Ok(Some(uasset)), Ok(Some(uexp))) => Some((uasset, uexp)),
+                (Err(e), _) | (_, Err(e)) => {
+                    return Err(IntegrationErr {
+                        mod_ctxt: None,
+                        kind: IntegrationErrKind::Repak(e),
+                    })
+                }
+                _ => None,
+            };
+            match datatable::merge(&path, original, &variants, &mut report.datatable_conflicts) {
+                Ok(merged) => merged,
+                Err(e) => {
+                    warn!(
+                        "failed to merge DataTable {path}, using highest priority version: {e:#}"
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let (uasset, uexp) = match &merged {
+            Some((uasset, uexp)) => (uasset, uexp),
+            None => (&variants[0].uasset, &variants[0].uexp),
+        };
+        write_file(&mut mod_pak, uasset, &format!("{path}.uasset"))
+            .and_then(|()| write_file(&mut mod_pak, uexp, &format!("{path}.uexp")))
             .map_err(|e| IntegrationErr {
                 mod_ctxt: None,
                 kind: IntegrationErrKind::Generic(e),
             })?;
-        hook_pcb(&mut pcb_asset);
-        write_asset(&mut mod_pak, pcb_asset, pcb_path).map_err(|e| IntegrationErr {
-            mod_ctxt: None,
-            kind: IntegrationErrKind::Generic(e),
-        })?;
     }
-    for patch_path in patch_paths {
-        let mut asset = deferred_assets[&patch_path]
-            .parse()
-            .map_err(|e| IntegrationErr {
-                mod_ctxt: None,
-                kind: IntegrationErrKind::Generic(e),
-            })?;
-        patch(&mut asset).map_err(|e| IntegrationErr {
-            mod_ctxt: None,
-            kind: IntegrationErrKind::Generic(e),
-        })?;
-        write_asset(&mut mod_pak, asset, patch_path).map_err(|e| IntegrationErr {
+
+    // assets modified by mint, parsed again when validating the bundle
+    let mut patched_assets = vec![];
+
+    for path in patch_paths {
+        let raw = &deferred_assets[path];
+        let patches = IntegrationPatch::iter()
+            .filter(|patch| {
+                patch.asset_paths().contains(&path)
+                    && !options.disabled_patches.contains(patch.id())
+            })
+            .collect::<Vec<_>>();
+        if patches.is_empty() {
+            // the game's own version doesn't need to be in the bundle
+            if modded_assets.contains(path)
+                && let (Some(uasset), Some(uexp)) = (&raw.uasset, &raw.uexp)
+            {
+                write_file(&mut mod_pak, uasset, &format!("{path}.uasset"))
+                    .and_then(|()| write_file(&mut mod_pak, uexp, &format!("{path}.uexp")))
+                    .map_err(|e| IntegrationErr {
+                        mod_ctxt: None,
+                        kind: IntegrationErrKind::Generic(e),
+                    })?;
+            }
+            continue;
+        }
+
+        let mut asset = match raw.parse() {
+            Ok(asset) => asset,
+            // patched assets can be missing from the game after an update
+            Err(e) if !raw.is_complete() => {
+                report
+                    .ineffective_patches
+                    .extend(patches.into_iter().map(|patch| IneffectivePatch {
+                        patch,
+                        asset: path.to_string(),
+                        reason: format!("{e:#}"),
+                    }));
+                continue;
+            }
+            Err(e) => {
+                return Err(IntegrationErr {
+                    mod_ctxt: None,
+                    kind: IntegrationErrKind::Generic(e),
+                })
+            }
+        };
+        for patch in patches {
+            let reason = match patch.apply(&mut asset) {
+                Ok(0) => "nothing to patch".to_string(),
+                Ok(_) => continue,
+                Err(e) => format!("{e:#}"),
+            };
+            report.ineffective_patches.push(IneffectivePatch {
+                patch,
+                asset: path.to_string(),
+                reason,
+            });
+        }
+        write_asset(&mut mod_pak, asset, path).map_err(|e| IntegrationErr {
             mod_ctxt: None,
             kind: IntegrationErrKind::Generic(e),
         })?;
+        patched_assets.push(path);
     }
 
-    let mut int_pak_reader = Cursor::new(include_bytes!("../assets/integration.pak"));
+    let mut int_pak_reader = Cursor::new(INTEGRATION_PAK);
     let int_pak = repak::PakBuilder::new()
         .reader(&mut int_pak_reader)
         .map_err(|e| IntegrationErr {
@@ -484,13 +821,10 @@ pub fn integrate<P: AsRef<Path>>(
         })
         .collect::<HashMap<_, _>>();
 
-    let int_path = (
-        "FSD/Content/_AssemblyStorm/ModIntegration/MI_SpawnMods.uasset",
-        "FSD/Content/_AssemblyStorm/ModIntegration/MI_SpawnMods.uexp",
-    );
-
-    int_files.remove(int_path.0);
-    int_files.remove(int_path.1);
+    for path in [SPAWN_MODS_PATH, MOD_STRUCT_PATH] {
+        int_files.remove(format!("{path}.uasset").as_str());
+        int_files.remove(format!("{path}.uexp").as_str());
+    }
 
     for (p, new_path) in int_files {
         write_file(
@@ -509,30 +843,36 @@ pub fn integrate<P: AsRef<Path>>(
         })?;
     }
 
-    let mut int_asset =
-        unreal_asset::Asset::new(
-            Cursor::new(int_pak.get(int_path.0, &mut int_pak_reader).map_err(|e| {
-                IntegrationErr {
+    let mut read_int_asset = |path: &str| -> Result<Asset<Cursor<Vec<u8>>>, IntegrationErr> {
+        let mut get = |ext: &str| {
+            int_pak
+                .get(&format!("{path}.{ext}"), &mut int_pak_reader)
+                .map_err(|e| IntegrationErr {
                     mod_ctxt: None,
                     kind: IntegrationErrKind::Repak(e),
-                }
-            })?),
-            Some(Cursor::new(
-                int_pak
-                    .get(int_path.1, &mut int_pak_reader)
-                    .map_err(|e| IntegrationErr {
-                        mod_ctxt: None,
-                        kind: IntegrationErrKind::Repak(e),
-                    })?,
-            )),
+                })
+        };
+        let uasset = get("uasset")?;
+        let uexp = get("uexp")?;
+        unreal_asset::Asset::new(
+            Cursor::new(uasset),
+            Some(Cursor::new(uexp)),
             unreal_asset::engine_version::EngineVersion::VER_UE4_27,
             None,
         )
         .map_err(|e| IntegrationErr {
             mod_ctxt: None,
             kind: IntegrationErrKind::UnrealAsset(e),
-        })?;
+        })
+    };
+
+    let mut mod_struct_asset = read_int_asset(MOD_STRUCT_PATH)?;
+    extend_mod_struct(&mut mod_struct_asset).map_err(|e| IntegrationErr {
+        mod_ctxt: None,
+        kind: IntegrationErrKind::Generic(e),
+    })?;
 
+    let mut int_asset = read_int_asset(SPAWN_MODS_PATH)?;
     inject_init_actors(
         &mut int_asset,
         init_spacerig_assets,
@@ -540,39 +880,56 @@ pub fn integrate<P: AsRef<Path>>(
         &mods,
     );
 
-    let mut int_out = (Cursor::new(vec![]), Cursor::new(vec![]));
-
-    int_asset
-        .write_data(&mut int_out.0, Some(&mut int_out.1))
-        .map_err(|e| IntegrationErr {
+    for (asset, path) in [
+        (mod_struct_asset, MOD_STRUCT_PATH),
+        (int_asset, SPAWN_MODS_PATH),
+    ] {
+        write_asset(&mut mod_pak, asset, path).map_err(|e| IntegrationErr {
             mod_ctxt: None,
-            kind: IntegrationErrKind::UnrealAsset(e),
+            kind: IntegrationErrKind::Generic(e),
         })?;
-    int_out.0.rewind().map_err(|e| IntegrationErr {
-        mod_ctxt: None,
-        kind: IntegrationErrKind::Generic(e.into()),
-    })?;
-    int_out.1.rewind().map_err(|e| IntegrationErr {
+    }
+
+    patched_assets.extend([MOD_STRUCT_PATH, SPAWN_MODS_PATH]);
+
+    mod_pak.write_index().map_err(|e| IntegrationErr {
         mod_ctxt: None,
-        kind: IntegrationErrKind::Generic(e.into()),
+        kind: IntegrationErrKind::Repak(e),
     })?;
 
-    write_file(&mut mod_pak, &mut int_out.0.into_inner(), int_path.0).map_err(|e| {
-        IntegrationErr {
+    let patched_assets = patched_assets
+        .into_iter()
+        .map(|path| normalize_path(path).to_string_lossy().replace('\\', "/"))
+        .collect::<Vec<_>>();
+    validate_bundle(tmp_mod_pak.path(), &written_entries.take(), &patched_assets)
+        .context("bundle failed validation, previous bundle was left in place")
+        .map_err(|e| IntegrationErr {
             mod_ctxt: None,
             kind: IntegrationErrKind::Generic(e),
-        }
-    })?;
-    write_file(&mut mod_pak, &mut int_out.1.into_inner(), int_path.1).map_err(|e| {
-        IntegrationErr {
+        })?;
+    tmp_mod_pak
+        .persist(&path_mod_pak)
+        .map_err(|e| IntegrationErr {
+            mod_ctxt: None,
+            kind: IntegrationErrKind::Generic(e.into()),
+        })?;
+
+    #[cfg(feature = "hook")]
+    if let Some(path_hook_dll) = &layout.hook_dll {
+        hook_dll::install(
+            path_hook_dll,
+            include_bytes!(env!("CARGO_CDYLIB_FILE_HOOK_hook")),
+        )
+        .with_context(|| format!("failed to install hook to {}", path_hook_dll.display()))
+        .map_err(|e| IntegrationErr {
             mod_ctxt: None,
             kind: IntegrationErrKind::Generic(e),
-        }
-    })?;
+        })?;
+    }
 
-    mod_pak.write_index().map_err(|e| IntegrationErr {
+    layout.finish().map_err(|e| IntegrationErr {
         mod_ctxt: None,
-        kind: IntegrationErrKind::Repak(e),
+        kind: IntegrationErrKind::Generic(e),
     })?;
 
     info!(
@@ -580,6 +937,81 @@ pub fn integrate<P: AsRef<Path>>(
         mods.len(),
         path_mod_pak.display()
     );
+    for conflict in &report.datatable_conflicts {
+        warn!("{conflict}");
+    }
+    for patch in &report.ineffective_patches {
+        warn!("{patch}");
+    }
+
+    Ok(report)
+}
+
+/// Reads back a freshly written bundle: checks that its index lists every entry of
+/// `written_entries` exactly once, that every entry can be decompressed and that `assets` (paths
+/// without extension) can be parsed.
+fn validate_bundle(path: &Path, written_entries: &[String], assets: &[String]) -> Result<()> {
+    let expected_entries = written_entries.iter().cloned().collect::<HashSet<_>>();
+    if expected_entries.len() != written_entries.len() {
+        let mut seen = HashSet::new();
+        let duplicate = written_entries.iter().find(|e| !seen.insert(*e)).unwrap();
+        bail!("{duplicate} was written more than once");
+    }
+
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let pak = repak::PakBuilder::new()
+        .reader(&mut reader)
+        .context("failed to read index")?;
+
+    let files = pak.files();
+    if files.len() != written_entries.len() {
+        bail!(
+            "index has {} entries but {} were written",
+            files.len(),
+            written_entries.len()
+        );
+    }
+    let files = files.into_iter().collect::<HashSet<_>>();
+    if let Some(missing) = expected_entries.difference(&files).next() {
+        bail!("{missing} is missing from the index");
+    }
+    if let Some(unexpected) = files.difference(&expected_entries).next() {
+        bail!("index contains unexpected entry {unexpected}");
+    }
+
+    // only the assets parsed below are kept in memory, the bundle can be several GB
+    let asset_files = assets
+        .iter()
+        .flat_map(|asset| [format!("{asset}.uasset"), format!("{asset}.uexp")])
+        .collect::<HashSet<_>>();
+    let mut contents = HashMap::new();
+    for file in files {
+        if asset_files.contains(&file) {
+            let data = pak
+                .get(&file, &mut reader)
+                .with_context(|| format!("failed to read {file}"))?;
+            contents.insert(file, data);
+        } else {
+            pak.read_file(&file, &mut reader, &mut io::sink())
+                .with_context(|| format!("failed to read {file}"))?;
+        }
+    }
+
+    for asset in assets {
+        let (Some(uasset), Some(uexp)) = (
+            contents.get(&format!("{asset}.uasset")),
+            contents.get(&format!("{asset}.uexp")),
+        ) else {
+            bail!("{asset} is missing");
+        };
+        Asset::new(
+            Cursor::new(uasset.as_slice()),
+            Some(Cursor::new(uexp.as_slice())),
+            unreal_asset::engine_version::EngineVersion::VER_UE4_27,
+            None,
+        )
+        .with_context(|| format!("failed to parse {asset}"))?;
+    }
 
     Ok(())
 }
@@ -677,7 +1109,16 @@ fn find_struct_property_named<'a>(
 
 /// "it's only 3 instructions"
 /// "how much boilerplate could there possibly be"
-fn hook_pcb<R: Read + Seek>(asset: &mut Asset<R>) {
+fn hook_pcb<R: Read + Seek>(asset: &mut Asset<R>) -> Result<usize> {
+    let has_begin_play = asset.asset_data.exports.iter().any(|e| {
+        matches!(e, unreal_asset::exports::Export::FunctionExport(func)
+            if func.get_base_export().object_name.get_content(|n| n == "ReceiveBeginPlay")
+                && func.struct_export.script_bytecode.is_some())
+    });
+    if !has_begin_play {
+        bail!("ReceiveBeginPlay function not found");
+    }
+
     let transform = get_import(
         asset,
         vec![
@@ -995,13 +1436,157 @@ fn hook_pcb<R: Read + Seek>(asset: &mut Asset<R>) {
         }
         .into(),
     );
+    Ok(1)
+}
+
+// Fields of the `MI_Mod` struct that are not part of the struct shipped in the integration pak.
+// They are appended by `extend_mod_struct` so blueprints built against the original struct keep
+// working.
+
+/// The mod's settings as `key=value` strings.
+const MOD_SETTINGS_FIELD: &str = "Settings_21_8D1C3F6E2B7A4E0B9F5D4C3A2E1B0F9A";
+/// Version name of the integrated file, empty if unknown.
+const MOD_VERSION_FIELD: &str = "Version_24_3F0A6C1E9B2D4F7A8E5C1B0D6A3F2E94";
+/// ID of the provider the mod was fetched from, such as `modio` or `http`.
+const MOD_PROVIDER_FIELD: &str = "Provider_27_A4E2B9C07D1F4B63925E8C1A0F7D3B56";
+/// mod.io ID of the mod, 0 if it isn't from mod.io.
+const MOD_MODIO_ID_FIELD: &str = "ModioId_30_6B8D2F4A1C9E4E0D8A7B3C5F2E1D0A97";
+/// Load priority set by the user.
+const MOD_PRIORITY_FIELD: &str = "Priority_33_C1F5A8E3B6D24A9F8E0B7C4D3A2F1E68";
+/// mod.io approval status: `Verified`, `Approved`, `Sandbox` or empty if unknown.
+const MOD_APPROVAL_FIELD: &str = "Approval_36_9E3D7B1F5A2C4D8E9B6A0F3C2D1E7B45";
+
+fn mod_struct_field<R: Read + Seek>(
+    asset: &mut Asset<R>,
+    name: &str,
+    serialized_type: &str,
+    element_size: i32,
+) -> FGenericProperty {
+    FGenericProperty {
+        name: asset.add_fname(name),
+        flags: EObjectFlags::RF_PUBLIC,
+        array_dim: unreal_asset::enums::EArrayDim::TArray,
+        element_size,
+        property_flags: EPropertyFlags::CPF_EDIT | EPropertyFlags::CPF_BLUEPRINT_VISIBLE,
+        rep_index: 0,
+        rep_notify_func: asset.add_fname("None"),
+        blueprint_replication_condition: unreal_asset::enums::ELifetimeCondition::CondNone,
+        serialized_type: Some(asset.add_fname(serialized_type)),
+    }
+}
+
+/// Values of the fields appended to the `MI_Mod` struct. The default values are used for the
+/// struct's default instance.
+#[derive(Debug, Default)]
+struct ModStructFields {
+    settings: Vec<String>,
+    version: String,
+    provider: String,
+    modio_id: i32,
+    priority: i32,
+    approval: String,
+}
+
+impl ModStructFields {
+    fn properties<R: Read + Seek>(self, asset: &mut Asset<R>) -> Vec<Property> {
+        let ancestry = Ancestry::new(FName::new_dummy("".to_owned(), 0));
+        let str_property = |asset: &mut Asset<R>, name: &str, value: String| -> Property {
+            StrProperty {
+                name: asset.add_fname(name),
+                ancestry: ancestry.clone(),
+                property_guid: None,
+                duplication_index: 0,
+                value: Some(value),
+            }
+            .into()
+        };
+        let int_property = |asset: &mut Asset<R>, name: &str, value: i32| -> Property {
+            IntProperty {
+                name: asset.add_fname(name),
+                ancestry: ancestry.clone(),
+                property_guid: None,
+                duplication_index: 0,
+                value,
+            }
+            .into()
+        };
+
+        vec![
+            ArrayProperty::from_arr(
+                asset.add_fname(MOD_SETTINGS_FIELD),
+                ancestry.clone(),
+                Some(asset.add_fname("StrProperty")),
+                self.settings
+                    .into_iter()
+                    .map(|setting| {
+                        StrProperty {
+                            name: FName::new_dummy("0".to_owned(), -2147483648),
+                            ancestry: ancestry.clone(),
+                            property_guid: None,
+                            duplication_index: 0,
+                            value: Some(setting),
+                        }
+                        .into()
+                    })
+                    .collect(),
+            )
+            .into(),
+            str_property(asset, MOD_VERSION_FIELD, self.version),
+            str_property(asset, MOD_PROVIDER_FIELD, self.provider),
+            int_property(asset, MOD_MODIO_ID_FIELD, self.modio_id),
+            int_property(asset, MOD_PRIORITY_FIELD, self.priority),
+            str_property(asset, MOD_APPROVAL_FIELD, self.approval),
+        ]
+    }
+}
+
+/// Names of the fields appended to the `MI_Mod` struct, in order.
+const MOD_STRUCT_FIELDS: [&str; 6] = [
+    MOD_SETTINGS_FIELD,
+    MOD_VERSION_FIELD,
+    MOD_PROVIDER_FIELD,
+    MOD_MODIO_ID_FIELD,
+    MOD_PRIORITY_FIELD,
+    MOD_APPROVAL_FIELD,
+];
+
+/// Appends the fields mint fills in on top of the original ones to the `MI_Mod` struct and to
+/// its default instance, which the game initializes new instances from.
+fn extend_mod_struct<R: Read + Seek>(asset: &mut Asset<R>) -> Result<()> {
+    let fields: Vec<FProperty> = vec![
+        FArrayProperty {
+            generic_property: mod_struct_field(asset, MOD_SETTINGS_FIELD, "ArrayProperty", 16),
+            inner: Box::new(mod_struct_field(asset, MOD_SETTINGS_FIELD, "StrProperty", 16).into()),
+        }
+        .into(),
+        mod_struct_field(asset, MOD_VERSION_FIELD, "StrProperty", 16).into(),
+        mod_struct_field(asset, MOD_PROVIDER_FIELD, "StrProperty", 16).into(),
+        mod_struct_field(asset, MOD_MODIO_ID_FIELD, "IntProperty", 4).into(),
+        mod_struct_field(asset, MOD_PRIORITY_FIELD, "IntProperty", 4).into(),
+        mod_struct_field(asset, MOD_APPROVAL_FIELD, "StrProperty", 16).into(),
+    ];
+
+    let defaults = ModStructFields::default().properties(asset);
+
+    let struct_export = asset
+        .asset_data
+        .exports
+        .iter_mut()
+        .find_map(|e| match e {
+            Export::UserDefinedStructExport(e) => Some(e),
+            _ => None,
+        })
+        .context("MI_Mod struct not found")?;
+    struct_export.struct_export.loaded_properties.extend(fields);
+    struct_export.default_struct_instance.extend(defaults);
+    Ok(())
 }
 
 fn inject_init_actors<R: Read + Seek>(
     asset: &mut Asset<R>,
     init_spacerig: HashSet<String>,
     init_cave: HashSet<String>,
-    mods: &[(ModInfo, PathBuf)],
+    mods: &[(ModInfo, ModConfig, PathBuf)],
 ) {
     let init_spacerig_fnames = init_spacerig
         .into_iter()
@@ -1016,7 +1601,7 @@ fn inject_init_actors<R: Read + Seek>(
 
     let structs = mods
         .iter()
-        .map(|(mod_info, _path)| {
+        .map(|(mod_info, mod_config, _path)| {
             StructProperty {
                 name: asset.add_fname("LoadedMods"),
                 ancestry: Ancestry::new(FName::new_dummy("".to_owned(), 0)),
@@ -1056,7 +1641,29 @@ fn inject_init_actors<R: Read + Seek>(
                     }
                     .into(),
                 ]
-                .to_vec(),
+                .into_iter()
+                .chain(
+                    ModStructFields {
+                        settings: mod_config
+                            .settings
+                            .iter()
+                            .map(|(key, value)| format!("{key}={value}"))
+                            .collect(),
+                        version: mod_info.version.clone().unwrap_or_default(),
+                        provider: mod_info.provider.to_string(),
+                        modio_id: mod_info.modio_id.unwrap_or_default() as i32,
+                        priority: mod_config.priority,
+                        approval: match mod_info.modio_tags.as_ref().map(|t| t.approval_status) {
+                            Some(ApprovalStatus::Verified) => "Verified",
+                            Some(ApprovalStatus::Approved) => "Approved",
+                            Some(ApprovalStatus::Sandbox) => "Sandbox",
+                            None => "",
+                        }
+                        .to_string(),
+                    }
+                    .properties(asset),
+                )
+                .collect(),
             }
             .into()
         })
@@ -1113,7 +1720,8 @@ fn inject_init_actors<R: Read + Seek>(
     }
 }
 
-fn patch<C: Seek + Read>(asset: &mut Asset<C>) -> Result<()> {
+/// Replaces calls to `FSDIsModdedServer` and `FSDIsModdedSandboxServer` with `false`.
+fn patch<C: Seek + Read>(asset: &mut Asset<C>) -> Result<usize> {
     let ver = AssetVersion::new_from(asset);
     let mut statements = extract_tracked_statements(asset, ver, &None);
 
@@ -1133,12 +1741,14 @@ fn patch<C: Seek + Read>(asset: &mut Asset<C>) -> Result<()> {
     fn patch_ismodded(
         is_modded: Option<PackageIndex>,
         is_modded_sandbox: Option<PackageIndex>,
+        replaced: &Cell<usize>,
         mut statement: TrackedStatement,
     ) -> Option<TrackedStatement> {
         walk(&mut statement.ex, &|ex| {
             if let KismetExpression::ExCallMath(f) = ex {
                 if Some(f.stack_node) == is_modded || Some(f.stack_node) == is_modded_sandbox {
-                    *ex = ExFalse::default().into()
+                    *ex = ExFalse::default().into();
+                    replaced.set(replaced.get() + 1);
                 }
             }
         });
@@ -1147,13 +1757,19 @@ fn patch<C: Seek + Read>(asset: &mut Asset<C>) -> Result<()> {
 
     let is_modded = find_function("FSDIsModdedServer");
     let is_modded_sandbox = find_function("FSDIsModdedSandboxServer");
+    if is_modded.is_none() && is_modded_sandbox.is_none() {
+        bail!("FSDIsModdedServer and FSDIsModdedSandboxServer are not imported");
+    }
 
+    let replaced = Cell::new(0);
     for (_pi, statements) in statements.iter_mut() {
         *statements = std::mem::take(statements)
             .into_iter()
-            .filter_map(|s| patch_ismodded(is_modded, is_modded_sandbox, s))
+            .filter_map(|s| patch_ismodded(is_modded, is_modded_sandbox, &replaced, s))
             .collect();
     }
-    inject_tracked_statements(asset, ver, statements);
-    Ok(())
+    if replaced.get() > 0 {
+        inject_tracked_statements(asset, ver, statements);
+    }
+    Ok(replaced.get())
 }
@@ -0,0 +1,579 @@
+//! Row level three-way merging of DataTable assets overridden by several mods.
+//!
+//! Every mod's version of a DataTable is compared to the version shipped with the game to find
+//! the rows it adds, changes or removes. The version of the mod with the highest priority is used
+//! as the base of the merged asset and the row changes of the other mods are carried over to it.
+//! Rows changed in different ways by several mods can't be merged automatically: the change with
+//! the highest priority is kept and the row is reported as a conflict.
+
+use std::io::{Cursor, Read, Seek};
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use unreal_asset::{
+    engine_version::EngineVersion,
+    exports::Export,
+    properties::{struct_property::StructProperty, Property},
+    types::{
+        fname::{FName, FNameContainer},
+        PackageIndex,
+    },
+    Asset,
+};
+
+use super::{get_import, Import};
+
+/// One mod's version of a DataTable.
+pub(super) struct DataTableVariant {
+    pub mod_name: String,
+    pub uasset: Vec<u8>,
+    pub uexp: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataTableConflictKind {
+    /// Several mods changed the row in different ways.
+    Diverging,
+    /// The row references objects that can't be carried over to the merged asset.
+    Unremappable,
+}
+
+/// A DataTable row that could not be merged.
+#[derive(Debug, Clone)]
+pub struct DataTableConflict {
+    pub asset: String,
+    pub row: String,
+    pub kind: DataTableConflictKind,
+    /// Mods that changed the row, in load order.
+    pub mods: Vec<String>,
+    /// Mod whose version of the row ended up in the bundle.
+    pub kept: String,
+}
+
+impl std::fmt::Display for DataTableConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            DataTableConflictKind::Diverging => write!(
+                f,
+                "row `{}` of {} was changed differently by {}, using the version from {}",
+                self.row,
+                self.asset,
+                self.mods.join(", "),
+                self.kept
+            ),
+            DataTableConflictKind::Unremappable => write!(
+                f,
+                "row `{}` of {} changed by {} references objects that could not be merged, using the version from {}",
+                self.row,
+                self.asset,
+                self.mods.join(", "),
+                self.kept
+            ),
+        }
+    }
+}
+
+/// Cheap check whether a `.uasset` could be a DataTable, so only those are kept around for
+/// merging.
+pub(super) fn is_candidate(uasset: &[u8]) -> bool {
+    uasset
+        .windows(b"DataTable".len())
+        .any(|w| w == b"DataTable")
+}
+
+/// Import chain from the outermost package to the object itself.
+type ImportPath = Vec<(String, String, String)>;
+
+/// Import paths referenced by rows of all variants. Canonical rows point into this table instead
+/// of the import map of the asset they came from so rows of different assets can be compared.
+#[derive(Default)]
+struct CanonicalImports {
+    paths: Vec<ImportPath>,
+}
+
+impl CanonicalImports {
+    fn index_of(&mut self, path: ImportPath) -> PackageIndex {
+        let i = match self.paths.iter().position(|p| *p == path) {
+            Some(i) => i,
+            None => {
+                self.paths.push(path);
+                self.paths.len() - 1
+            }
+        };
+        PackageIndex::new(-(i as i32) - 1)
+    }
+
+    fn path(&self, index: PackageIndex) -> &ImportPath {
+        &self.paths[(-index.index - 1) as usize]
+    }
+}
+
+/// A row as read from an asset along with its canonical form.
+struct Row {
+    native: StructProperty,
+    /// Row independent of the name and import maps of the asset. References to objects that are
+    /// not imports are kept as read.
+    canonical: StructProperty,
+    /// Whether the row only references imports, so it can be transferred to another asset.
+    remappable: bool,
+}
+
+impl Row {
+    fn same_as(&self, other: &Row) -> bool {
+        self.canonical == other.canonical
+    }
+}
+
+type Rows = IndexMap<(String, i32), Row>;
+
+fn parse(uasset: &[u8], uexp: &[u8]) -> Result<Asset<Cursor<Vec<u8>>>> {
+    Ok(Asset::new(
+        Cursor::new(uasset.to_vec()),
+        Some(Cursor::new(uexp.to_vec())),
+        EngineVersion::VER_UE4_27,
+        None,
+    )?)
+}
+
+fn table_mut<R: Read + Seek>(asset: &mut Asset<R>) -> Option<&mut Vec<StructProperty>> {
+    asset
+        .asset_data
+        .exports
+        .iter_mut()
+        .find_map(|export| match export {
+            Export::DataTableExport(export) => Some(&mut export.table.data),
+            _ => None,
+        })
+}
+
+fn row_key(row: &StructProperty) -> (String, i32) {
+    (row.name.get_owned_content(), row.name.get_number())
+}
+
+fn import_path<R: Read + Seek>(asset: &Asset<R>, mut index: PackageIndex) -> Option<ImportPath> {
+    let mut path = vec![];
+    while index.is_import() {
+        let import = asset.imports.get((-index.index - 1) as usize)?;
+        path.push((
+            import.class_package.get_owned_content(),
+            import.class_name.get_owned_content(),
+            import.object_name.get_owned_content(),
+        ));
+        index = import.outer_index;
+    }
+    // a chain not ending at the root points into the asset's own exports
+    (index.index == 0).then(|| {
+        path.reverse();
+        path
+    })
+}
+
+/// Calls `f` on every object reference in `property`, stopping at the first one for which it
+/// returns `false`.
+fn visit_object_refs(
+    property: &mut Property,
+    f: &mut dyn FnMut(&mut PackageIndex) -> bool,
+) -> bool {
+    match property {
+        Property::ObjectProperty(p) => p.value.index == 0 || f(&mut p.value),
+        Property::StructProperty(p) => p.value.iter_mut().all(|p| visit_object_refs(p, f)),
+        Property::ArrayProperty(p) => p.value.iter_mut().all(|p| visit_object_refs(p, f)),
+        Property::SetProperty(p) => p
+            .value
+            .value
+            .iter_mut()
+            .chain(p.removed_items.value.iter_mut())
+            .all(|p| visit_object_refs(p, f)),
+        Property::MapProperty(p) => {
+            let mut ok = true;
+            p.value = std::mem::take(&mut p.value)
+                .into_iter()
+                .map(|(mut k, mut v)| {
+                    ok = ok && visit_object_refs(&mut k, f) && visit_object_refs(&mut v, f);
+                    (k, v)
+                })
+                .collect();
+            ok
+        }
+        _ => true,
+    }
+}
+
+/// Converts a row to a form independent of the name and import maps of `asset`. Also returns
+/// whether every object reference could be converted, references that aren't imports are left
+/// as they are.
+fn canonicalize<R: Read + Seek>(
+    asset: &Asset<R>,
+    row: &StructProperty,
+    imports: &mut CanonicalImports,
+) -> (StructProperty, bool) {
+    let mut row = row.clone();
+    row.traverse_fnames(&mut |name: &mut FName| {
+        *name = FName::new_dummy(name.get_owned_content(), name.get_number());
+    });
+    let mut remappable = true;
+    for property in &mut row.value {
+        visit_object_refs(property, &mut |index| {
+            match import_path(asset, *index) {
+                Some(path) => *index = imports.index_of(path),
+                None => remappable = false,
+            }
+            true
+        });
+    }
+    (row, remappable)
+}
+
+/// Converts a canonical row back to a row using the name and import maps of `asset`, adding
+/// names and imports as necessary.
+fn transfer<R: Read + Seek>(
+    asset: &mut Asset<R>,
+    mut row: StructProperty,
+    imports: &CanonicalImports,
+) -> StructProperty {
+    row.traverse_fnames(&mut |name: &mut FName| {
+        let number = name.get_number();
+        let mut new_name = asset.add_fname(&name.get_owned_content());
+        if let FName::Backed { number: n, .. } = &mut new_name {
+            *n = number;
+        }
+        *name = new_name;
+    });
+    for property in &mut row.value {
+        visit_object_refs(property, &mut |index| {
+            let path = imports.path(*index);
+            *index = get_import(
+                asset,
+                path.iter()
+                    .map(|(package, class, object)| Import::new(package, class, object))
+                    .collect(),
+            );
+            true
+        });
+    }
+    row
+}
+
+fn read_rows<R: Read + Seek>(asset: &mut Asset<R>, imports: &mut CanonicalImports) -> Option<Rows> {
+    let table = table_mut(asset)?.clone();
+    Some(
+        table
+            .into_iter()
+            .map(|native| {
+                let (canonical, remappable) = canonicalize(asset, &native, imports);
+                (
+                    row_key(&native),
+                    Row {
+                        native,
+                        canonical,
+                        remappable,
+                    },
+                )
+            })
+            .collect(),
+    )
+}
+
+/// A row of the merged DataTable.
+enum MergedRow {
+    /// Row of the highest priority variant, which is the base of the merged asset.
+    Native(StructProperty),
+    /// Canonical row of another variant that still has to be transferred to the merged asset.
+    Canonical(StructProperty),
+}
+
+/// Merges the rows of `variants`, ordered from highest to lowest priority, against the rows of
+/// the game's version of the DataTable at `path`.
+fn merge_rows(
+    path: &str,
+    original_rows: &Rows,
+    variants: &[DataTableVariant],
+    variant_rows: &[Rows],
+    conflicts: &mut Vec<DataTableConflict>,
+) -> Vec<MergedRow> {
+    let mut keys = original_rows.keys().cloned().collect::<Vec<_>>();
+    for rows in variant_rows {
+        for key in rows.keys() {
+            if !original_rows.contains_key(key) && !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+    }
+
+    let mut merged = vec![];
+    for key in keys {
+        let original = original_rows.get(&key);
+
+        // (variant index, row or None if removed) of every variant that changed the row
+        let changes = variant_rows
+            .iter()
+            .enumerate()
+            .filter_map(|(i, rows)| match (original, rows.get(&key)) {
+                (Some(o), Some(r)) if o.same_as(r) => None,
+                (None, None) => None,
+                (_, r) => Some((i, r)),
+            })
+            .collect::<Vec<_>>();
+
+        let Some(&(winner, row)) = changes.first() else {
+            // unchanged by every mod so the highest priority variant still contains it
+            merged.push(MergedRow::Native(variant_rows[0][&key].native.clone()));
+            continue;
+        };
+
+        let diverging = changes[1..].iter().any(|(_, other)| match (row, *other) {
+            (Some(a), Some(b)) => !a.same_as(b),
+            (None, None) => false,
+            _ => true,
+        });
+        let mods = || {
+            changes
+                .iter()
+                .map(|(i, _)| variants[*i].mod_name.clone())
+                .collect::<Vec<_>>()
+        };
+        if diverging {
+            conflicts.push(DataTableConflict {
+                asset: path.to_string(),
+                row: key.0.clone(),
+                kind: DataTableConflictKind::Diverging,
+                mods: mods(),
+                kept: variants[winner].mod_name.clone(),
+            });
+        }
+
+        match row {
+            None => {}
+            Some(row) if winner == 0 => merged.push(MergedRow::Native(row.native.clone())),
+            Some(row) if row.remappable => merged.push(MergedRow::Canonical(row.canonical.clone())),
+            Some(_) => {
+                // keep whatever the highest priority variant has
+                let kept = variant_rows[0].get(&key);
+                conflicts.push(DataTableConflict {
+                    asset: path.to_string(),
+                    row: key.0.clone(),
+                    kind: DataTableConflictKind::Unremappable,
+                    mods: mods(),
+                    kept: variants[0].mod_name.clone(),
+                });
+                if let Some(kept) = kept {
+                    merged.push(MergedRow::Native(kept.native.clone()));
+                }
+            }
+        }
+    }
+    merged
+}
+
+/// Merges `variants`, ordered from highest to lowest priority, against the game's `original`
+/// version of the DataTable at `path`. Returns the merged `.uasset` and `.uexp` or `None` if
+/// any variant isn't a DataTable, in which case the first variant should be used unchanged.
+pub(super) fn merge(
+    path: &str,
+    original: Option<(Vec<u8>, Vec<u8>)>,
+    variants: &[DataTableVariant],
+    conflicts: &mut Vec<DataTableConflict>,
+) -> Result<Option<(Vec<u8>, Vec<u8>)>> {
+    let mut imports = CanonicalImports::default();
+
+    let original_rows = match original {
+        Some((uasset, uexp)) => {
+            let mut asset = parse(&uasset, &uexp).context("failed to parse game DataTable")?;
+            match read_rows(&mut asset, &mut imports) {
+                Some(rows) => rows,
+                None => return Ok(None),
+            }
+        }
+        None => Rows::new(),
+    };
+
+    let mut assets = vec![];
+    let mut variant_rows = vec![];
+    for variant in variants {
+        let mut asset = parse(&variant.uasset, &variant.uexp)
+            .with_context(|| format!("failed to parse DataTable from {}", variant.mod_name))?;
+        let Some(rows) = read_rows(&mut asset, &mut imports) else {
+            return Ok(None);
+        };
+        assets.push(asset);
+        variant_rows.push(rows);
+    }
+
+    let merged = merge_rows(path, &original_rows, variants, &variant_rows, conflicts);
+
+    let asset = &mut assets[0];
+    let merged = merged
+        .into_iter()
+        .map(|row| match row {
+            MergedRow::Native(row) => row,
+            MergedRow::Canonical(row) => transfer(asset, row, &imports),
+        })
+        .collect();
+    *table_mut(asset).unwrap() = merged;
+
+    let mut data_out = (Cursor::new(vec![]), Cursor::new(vec![]));
+    asset.write_data(&mut data_out.0, Some(&mut data_out.1))?;
+    Ok(Some((data_out.0.into_inner(), data_out.1.into_inner())))
+}
+
+#[cfg(test)]
+mod tests {
+    use unreal_asset::properties::{int_property::IntProperty, object_property::ObjectProperty};
+    use unreal_asset::unversioned::ancestry::Ancestry;
+
+    use super::*;
+
+    fn ancestry() -> Ancestry {
+        Ancestry::new(FName::new_dummy("".to_owned(), 0))
+    }
+
+    /// Builds a canonical row with an int value and an object reference, which is an import if
+    /// negative and one of the asset's exports if positive.
+    fn row(name: &str, value: i32, object: i32) -> Row {
+        let row = StructProperty {
+            name: FName::new_dummy(name.to_owned(), 0),
+            ancestry: ancestry(),
+            struct_type: Some(FName::new_dummy("Row".to_owned(), 0)),
+            struct_guid: None,
+            property_guid: None,
+            duplication_index: 0,
+            serialize_none: true,
+            value: vec![
+                IntProperty {
+                    name: FName::new_dummy("Value".to_owned(), 0),
+                    ancestry: ancestry(),
+                    property_guid: None,
+                    duplication_index: 0,
+                    value,
+                }
+                .into(),
+                ObjectProperty {
+                    name: FName::new_dummy("Object".to_owned(), 0),
+                    ancestry: ancestry(),
+                    property_guid: None,
+                    duplication_index: 0,
+                    value: PackageIndex::new(object),
+                }
+                .into(),
+            ],
+        };
+        Row {
+            native: row.clone(),
+            canonical: row,
+            remappable: object <= 0,
+        }
+    }
+
+    fn rows(rows: impl IntoIterator<Item = Row>) -> Rows {
+        rows.into_iter()
+            .map(|row| (row_key(&row.native), row))
+            .collect()
+    }
+
+    fn variants(names: &[&str]) -> Vec<DataTableVariant> {
+        names
+            .iter()
+            .map(|name| DataTableVariant {
+                mod_name: name.to_string(),
+                uasset: vec![],
+                uexp: vec![],
+            })
+            .collect()
+    }
+
+    /// Rows as `name=value`, followed by `*` for rows that have to be transferred.
+    fn values(merged: &[MergedRow]) -> Vec<String> {
+        merged
+            .iter()
+            .map(|row| {
+                let (row, suffix) = match row {
+                    MergedRow::Native(row) => (row, ""),
+                    MergedRow::Canonical(row) => (row, "*"),
+                };
+                let Property::IntProperty(value) = &row.value[0] else {
+                    panic!("row has no int value");
+                };
+                format!("{}={}{suffix}", row.name.get_owned_content(), value.value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_disjoint_edits() {
+        let original = rows([row("A", 1, -1), row("B", 2, -1), row("C", 3, -1)]);
+        let a = rows([row("A", 10, -1), row("B", 2, -1), row("C", 3, -1)]);
+        // removes C and adds D
+        let b = rows([row("A", 1, -1), row("B", 20, -1), row("D", 4, -2)]);
+
+        let mut conflicts = vec![];
+        let merged = merge_rows(
+            "dt",
+            &original,
+            &variants(&["a", "b"]),
+            &[a, b],
+            &mut conflicts,
+        );
+
+        assert!(conflicts.is_empty());
+        assert_eq!(values(&merged), ["A=10", "B=20*", "D=4*"]);
+    }
+
+    #[test]
+    fn test_merge_conflict() {
+        let original = rows([row("A", 1, -1)]);
+        let a = rows([row("A", 10, -1)]);
+        let b = rows([row("A", 20, -1)]);
+        // same change as a, not a conflict on its own
+        let c = rows([row("A", 10, -1)]);
+
+        let mut conflicts = vec![];
+        let merged = merge_rows(
+            "dt",
+            &original,
+            &variants(&["a", "b", "c"]),
+            &[a, b, c],
+            &mut conflicts,
+        );
+
+        assert_eq!(values(&merged), ["A=10"]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, DataTableConflictKind::Diverging);
+        assert_eq!(conflicts[0].mods, ["a", "b", "c"]);
+        assert_eq!(conflicts[0].kept, "a");
+    }
+
+    #[test]
+    fn test_merge_non_import_refs() {
+        // rows referencing the asset's own exports are unchanged if identical to the game's
+        let original = rows([row("A", 1, 1), row("B", 2, 1)]);
+        let a = rows([row("A", 10, -1), row("B", 2, 1)]);
+        let b = rows([row("A", 1, 1), row("B", 2, 1)]);
+
+        let mut conflicts = vec![];
+        let merged = merge_rows(
+            "dt",
+            &original,
+            &variants(&["a", "b"]),
+            &[a, b],
+            &mut conflicts,
+        );
+        assert!(conflicts.is_empty());
+        assert_eq!(values(&merged), ["A=10", "B=2"]);
+
+        // but can't be carried over to the merged asset if changed by a lower priority mod
+        let a = rows([row("A", 10, -1), row("B", 2, 1)]);
+        let b = rows([row("A", 1, 1), row("B", 20, 1)]);
+        let merged = merge_rows(
+            "dt",
+            &original,
+            &variants(&["a", "b"]),
+            &[a, b],
+            &mut conflicts,
+        );
+        assert_eq!(values(&merged), ["A=10", "B=2"]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, DataTableConflictKind::Unremappable);
+        assert_eq!(conflicts[0].row, "B");
+        assert_eq!(conflicts[0].kept, "a");
+    }
+}
@@ -0,0 +1,117 @@
+//! Installation of the hook DLL next to the game executable. The DLL takes the name of a system
+//! library that other mod loaders like to replace as well, so mint only overwrites or removes a
+//! DLL it can identify as its own, by hash or for hooks installed by older versions by their
+//! contents. Any other DLL in the way is moved aside and put back on uninstall.
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use fs_err as fs;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+/// Written next to the hook DLL to recognize it later.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct HookMarker {
+    sha256: String,
+    version: String,
+}
+
+fn with_suffix(dll: &Path, suffix: &str) -> PathBuf {
+    let mut path = dll.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+fn marker_path(dll: &Path) -> PathBuf {
+    with_suffix(dll, ".mint.json")
+}
+
+fn backup_path(dll: &Path) -> PathBuf {
+    with_suffix(dll, ".mint-backup")
+}
+
+fn hash(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn read_marker(dll: &Path) -> Option<HookMarker> {
+    fs::read(marker_path(dll))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+}
+
+/// Byte strings every build of the hook contains: the exports of both system libraries it takes
+/// the name of and the pak it looks for. No system library exports both.
+const HOOK_SIGNATURE: &[&[u8]] = &[b"X3DAudioInitialize", b"D3DPERF_BeginEvent", b"mods_P.pak"];
+
+/// Whether `data` looks like a hook installed by a mint version that didn't write markers yet.
+fn is_unmarked_hook(data: &[u8]) -> bool {
+    HOOK_SIGNATURE
+        .iter()
+        .all(|signature| data.windows(signature.len()).any(|w| w == *signature))
+}
+
+/// Whether `data`, the current contents of `dll`, was installed by mint.
+fn is_ours(dll: &Path, data: &[u8]) -> bool {
+    read_marker(dll).is_some_and(|marker| marker.sha256 == hash(data)) || is_unmarked_hook(data)
+}
+
+pub(super) fn install(dll: &Path, hook: &[u8]) -> Result<()> {
+    let hook_hash = hash(hook);
+    let up_to_date = match fs::read(dll) {
+        Ok(existing) if hash(&existing) == hook_hash => true,
+        // an older version of the hook
+        Ok(existing) if is_ours(dll, &existing) => false,
+        Ok(_) => {
+            let backup = backup_path(dll);
+            if backup.exists() {
+                bail!(
+                    "{} was not installed by mint and a backup already exists at {}",
+                    dll.display(),
+                    backup.display()
+                );
+            }
+            fs::rename(dll, &backup)?;
+            info!("backed up {} to {}", dll.display(), backup.display());
+            false
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => false,
+        Err(e) => return Err(e.into()),
+    };
+    if !up_to_date {
+        fs::write(dll, hook)?;
+    }
+    fs::write(
+        marker_path(dll),
+        serde_json::to_vec_pretty(&HookMarker {
+            sha256: hook_hash,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        })?,
+    )?;
+    Ok(())
+}
+
+pub(super) fn uninstall(dll: &Path) -> Result<()> {
+    match fs::read(dll) {
+        Ok(existing) if is_ours(dll, &existing) => fs::remove_file(dll)?,
+        Ok(_) => warn!(
+            "not removing {} as it was not installed by mint",
+            dll.display()
+        ),
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    match fs::remove_file(marker_path(dll)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+
+    let backup = backup_path(dll);
+    if backup.exists() && !dll.exists() {
+        fs::rename(&backup, dll)?;
+        info!("restored {} from {}", dll.display(), backup.display());
+    }
+    Ok(())
+}
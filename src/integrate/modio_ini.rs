@@ -0,0 +1,264 @@
+//! Changes to the config of the official mod.io integration.
+//!
+//! The official integration enables *every mod the user has installed* once it gets re-enabled.
+//! When uninstalling we do the user a favor and explicitly list all installed mods in
+//! `GameUserSettings.ini` so they stay disabled when the game is launched again. Since we have
+//! mod.io IDs anyway, the mods of the active profile are enabled instead, which makes uninstalling
+//! work as installing for the official integration.
+//!
+//! The ini is only written after the user saw a preview of the changes, and a timestamped backup
+//! is kept next to it so the change can be undone.
+
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+use fs_err as fs;
+use mint_lib::{DRGInstallation, DRGInstallationType};
+use tracing::info;
+
+const UGC_SECTION: &str = "/Script/FSD.UserGeneratedContent";
+const BACKUP_SUFFIX: &str = ".mint-backup";
+
+fn config_path(installation: &DRGInstallation) -> PathBuf {
+    let platform = match installation.installation_type {
+        DRGInstallationType::Steam => "WindowsNoEditor",
+        DRGInstallationType::Xbox => "WinGDK",
+    };
+    installation
+        .root
+        .join("Saved/Config")
+        .join(platform)
+        .join("GameUserSettings.ini")
+}
+
+/// A line of [`ModioIniUpdate::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Unchanged(String),
+    Added(String),
+    Removed(String),
+}
+
+/// A pending rewrite of `GameUserSettings.ini`.
+#[derive(Debug, Clone)]
+pub struct ModioIniUpdate {
+    pub path: PathBuf,
+    pub old: String,
+    pub new: String,
+}
+
+impl ModioIniUpdate {
+    pub fn diff(&self) -> Vec<DiffLine> {
+        let a = self.old.lines().collect::<Vec<_>>();
+        let b = self.new.lines().collect::<Vec<_>>();
+
+        // longest common subsequence of every pair of suffixes
+        let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i][j] = if a[i] == b[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut diff = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if i < a.len() && j < b.len() && a[i] == b[j] {
+                diff.push(DiffLine::Unchanged(a[i].to_string()));
+                i += 1;
+                j += 1;
+            } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                diff.push(DiffLine::Added(b[j].to_string()));
+                j += 1;
+            } else {
+                diff.push(DiffLine::Removed(a[i].to_string()));
+                i += 1;
+            }
+        }
+        diff
+    }
+
+    /// Backs up the ini and writes the new version. Returns the path of the backup.
+    pub fn apply(&self) -> Result<PathBuf> {
+        let current = fs::read_to_string(&self.path)?;
+        if current != self.old {
+            bail!("{} changed since the preview was made", self.path.display());
+        }
+        let backup = backup(&self.path)?;
+        fs::write(&self.path, &self.new)?;
+        info!("updated {}", self.path.display());
+        Ok(backup)
+    }
+}
+
+fn backup(path: &Path) -> Result<PathBuf> {
+    let mut timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let backup = loop {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(format!(".{timestamp}{BACKUP_SUFFIX}"));
+        let backup = PathBuf::from(backup);
+        if !backup.exists() {
+            break backup;
+        }
+        // never overwrite an earlier backup, even one made within the same second
+        timestamp += 1;
+    };
+    fs::copy(path, &backup)?;
+    info!("backed up {} to {}", path.display(), backup.display());
+    Ok(backup)
+}
+
+/// Computes the changes needed to make the official integration enable exactly `modio_mods`.
+/// Returns `None` if the official integration isn't set up for the installation, including when it
+/// hasn't recorded any installed mods yet, or nothing would change, and an error if its config
+/// can't be located.
+pub fn plan_modio_ini_update<P: AsRef<Path>>(
+    path_pak: P,
+    modio_mods: &HashSet<u32>,
+) -> Result<Option<ModioIniUpdate>> {
+    #[derive(Debug, serde::Deserialize)]
+    struct ModioState {
+        #[serde(rename = "Mods")]
+        mods: Vec<ModioMod>,
+    }
+    #[derive(Debug, serde::Deserialize)]
+    struct ModioMod {
+        #[serde(rename = "ID")]
+        id: u32,
+    }
+
+    let installation = DRGInstallation::from_pak_path(path_pak)?;
+    let Some(modio_dir) = installation.modio_directory() else {
+        if matches!(installation.installation_type, DRGInstallationType::Xbox) {
+            return Ok(None);
+        }
+        // under Proton the mod.io directory is inside the Steam library
+        bail!("official integration config not found: could not locate the Steam library");
+    };
+    let path = config_path(&installation);
+    let old = match fs::read_to_string(&path) {
+        Ok(old) => old,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let state_path = modio_dir.join("metadata/state.json");
+    let installed_mods = match fs::read(&state_path) {
+        Ok(data) => {
+            serde_json::from_slice::<ModioState>(&data)
+                .with_context(|| format!("failed to parse {}", state_path.display()))?
+                .mods
+        }
+        // without the list of installed mods the ones not in `modio_mods` can't be disabled, so
+        // leave the config alone instead of clearing it
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            info!(
+                "{} not found, not updating the mod.io config",
+                state_path.display()
+            );
+            return Ok(None);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let local_mods: Vec<String> = match installation.root.join("Mods").read_dir() {
+        Ok(entries) => entries
+            .map(|f| {
+                let f = f?;
+                Ok((!f.path().is_file()).then(|| f.file_name().to_string_lossy().to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect(),
+        Err(e) if e.kind() == ErrorKind::NotFound => vec![],
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut config = ini::Ini::load_from_str(&old)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    let ignore_keys = HashSet::from(["CurrentModioUserId"]);
+    let ugc_section = config
+        .entry(Some(UGC_SECTION.to_string()))
+        .or_insert_with(Default::default);
+    let to_remove = ugc_section
+        .iter()
+        .map(|(k, _)| k)
+        .filter(|k| !ignore_keys.contains(k))
+        .map(str::to_owned)
+        .collect::<Vec<String>>();
+    for r in to_remove {
+        let _ = ugc_section.remove_all(r);
+    }
+    for m in installed_mods {
+        ugc_section.insert(
+            m.id.to_string(),
+            if modio_mods.contains(&m.id) {
+                "True"
+            } else {
+                "False"
+            },
+        );
+    }
+    for m in local_mods {
+        ugc_section.insert(m, "False");
+    }
+    ugc_section.insert("CheckGameversion", "False");
+
+    let mut new = vec![];
+    config.write_to_opt(
+        &mut new,
+        ini::WriteOption {
+            line_separator: ini::LineSeparator::CRLF,
+            ..Default::default()
+        },
+    )?;
+    let new = String::from_utf8(new)?;
+
+    Ok((new != old).then_some(ModioIniUpdate { path, old, new }))
+}
+
+/// Backups of `GameUserSettings.ini` made by mint, newest first.
+pub fn modio_ini_backups<P: AsRef<Path>>(path_pak: P) -> Result<Vec<PathBuf>> {
+    let installation = DRGInstallation::from_pak_path(path_pak)?;
+    let path = config_path(&installation);
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return Ok(vec![]);
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+
+    let mut backups = match dir.read_dir() {
+        Ok(entries) => entries
+            .map(|e| Ok(e?.file_name().to_string_lossy().to_string()))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .filter_map(|f| {
+                let timestamp = f.strip_prefix(&prefix)?.strip_suffix(BACKUP_SUFFIX)?;
+                Some((timestamp.parse::<u64>().ok()?, dir.join(f)))
+            })
+            .collect::<Vec<_>>(),
+        Err(e) if e.kind() == ErrorKind::NotFound => vec![],
+        Err(e) => return Err(e.into()),
+    };
+    backups.sort_by(|a, b| b.cmp(a));
+    Ok(backups.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Replaces `GameUserSettings.ini` with `backup`, backing up the current version first.
+pub fn restore_modio_ini<P: AsRef<Path>>(path_pak: P, backup_path: &Path) -> Result<()> {
+    let installation = DRGInstallation::from_pak_path(path_pak)?;
+    let path = config_path(&installation);
+    if path.exists() {
+        backup(&path)?;
+    }
+    fs::copy(backup_path, &path)?;
+    info!("restored {} from {}", path.display(), backup_path.display());
+    Ok(())
+}
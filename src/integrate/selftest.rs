@@ -0,0 +1,276 @@
+//! Dry run of everything mint changes in game assets, to find out whether a game update broke any
+//! of it before players do. Patches are applied to the assets of the game pak in memory and the
+//! blueprints of the integration pak are checked for the properties filled in when integrating.
+//! Nothing is written to disk.
+
+use std::io::{BufReader, Cursor, Read, Seek};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use fs_err as fs;
+use strum::IntoEnumIterator;
+use unreal_asset::{
+    engine_version::EngineVersion, exports::Export, fproperty::FProperty, properties::Property,
+    Asset,
+};
+
+use super::{
+    extend_mod_struct, find_array_property_named, find_export_named, find_struct_property_named,
+    IntegrationPatch, INTEGRATION_PAK, MOD_STRUCT_FIELDS, MOD_STRUCT_PATH, SPAWN_MODS_PATH,
+};
+
+/// Outcome of checking one asset.
+#[derive(Debug, Clone)]
+pub struct SelftestCheck {
+    pub name: String,
+    pub asset: String,
+    pub passed: bool,
+    /// What was found or changed, or why the check failed.
+    pub details: Vec<String>,
+}
+
+impl std::fmt::Display for SelftestCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {}: {}",
+            if self.passed { "PASS" } else { "FAIL" },
+            self.name,
+            self.asset
+        )?;
+        for detail in &self.details {
+            write!(f, "\n    {detail}")?;
+        }
+        Ok(())
+    }
+}
+
+fn read_asset<R: Read + Seek>(
+    pak: &repak::PakReader,
+    reader: &mut R,
+    path: &str,
+) -> Result<Asset<Cursor<Vec<u8>>>> {
+    let uasset = pak
+        .get(&format!("{path}.uasset"), reader)
+        .with_context(|| format!("failed to read {path}.uasset"))?;
+    let uexp = pak
+        .get(&format!("{path}.uexp"), reader)
+        .with_context(|| format!("failed to read {path}.uexp"))?;
+    Asset::new(
+        Cursor::new(uasset),
+        Some(Cursor::new(uexp)),
+        EngineVersion::VER_UE4_27,
+        None,
+    )
+    .with_context(|| format!("failed to parse {path}"))
+}
+
+/// Makes sure the asset can still be serialized after being changed. Returns the `.uasset` and
+/// `.uexp`.
+fn write_asset<R: Read + Seek>(asset: &Asset<R>) -> Result<(Vec<u8>, Vec<u8>)> {
+    let mut data_out = (Cursor::new(vec![]), Cursor::new(vec![]));
+    asset
+        .write_data(&mut data_out.0, Some(&mut data_out.1))
+        .context("failed to serialize patched asset")?;
+    Ok((data_out.0.into_inner(), data_out.1.into_inner()))
+}
+
+fn check_patch<R: Read + Seek>(
+    patch: IntegrationPatch,
+    pak: &repak::PakReader,
+    reader: &mut R,
+    path: &str,
+) -> SelftestCheck {
+    let mut details = vec![];
+    let result = (|| -> Result<bool> {
+        let mut asset = read_asset(pak, reader, path)?;
+        let imports_before = asset.imports.len();
+
+        let changes = patch.apply(&mut asset)?;
+        details.push(format!("{changes} statements rewritten"));
+        for import in &asset.imports[imports_before..] {
+            details.push(format!(
+                "created import {} {} {}",
+                import.class_package.get_owned_content(),
+                import.class_name.get_owned_content(),
+                import.object_name.get_owned_content()
+            ));
+        }
+
+        write_asset(&asset)?;
+        Ok(changes > 0)
+    })();
+
+    let passed = match result {
+        Ok(passed) => passed,
+        Err(e) => {
+            details.push(format!("{e:#}"));
+            false
+        }
+    };
+    SelftestCheck {
+        name: format!("patch \"{}\"", patch.name()),
+        asset: path.to_string(),
+        passed,
+        details,
+    }
+}
+
+fn check_spawn_mods<R: Read + Seek>(pak: &repak::PakReader, reader: &mut R) -> SelftestCheck {
+    let mut details = vec![];
+    let result = (|| -> Result<bool> {
+        let mut asset = read_asset(pak, reader, SPAWN_MODS_PATH)?;
+        let Some(export) = find_export_named(&mut asset, "Default__MI_SpawnMods_C") else {
+            details.push("Default__MI_SpawnMods_C not found".to_string());
+            return Ok(false);
+        };
+        details.push("found Default__MI_SpawnMods_C".to_string());
+
+        let mut passed = true;
+        for name in ["SpaceRigMods", "CaveMods"] {
+            let found = find_array_property_named(export, name).is_some();
+            details.push(format!(
+                "{} array property {name}",
+                if found { "found" } else { "missing" }
+            ));
+            passed &= found;
+        }
+
+        let config = find_struct_property_named(export, "Config").map(|(_, p)| {
+            (
+                matches!(p.value.first(), Some(Property::StrProperty(_))),
+                matches!(p.value.get(1), Some(Property::ArrayProperty(_))),
+            )
+        });
+        match config {
+            Some((version, loaded_mods)) => {
+                details.push("found struct property Config".to_string());
+                for (found, name) in [(version, "version"), (loaded_mods, "LoadedMods")] {
+                    details.push(format!(
+                        "{} Config {name} field",
+                        if found { "found" } else { "missing" }
+                    ));
+                    passed &= found;
+                }
+            }
+            None => {
+                details.push("missing struct property Config".to_string());
+                passed = false;
+            }
+        }
+        Ok(passed)
+    })();
+
+    let passed = match result {
+        Ok(passed) => passed,
+        Err(e) => {
+            details.push(format!("{e:#}"));
+            false
+        }
+    };
+    SelftestCheck {
+        name: "mod list".to_string(),
+        asset: SPAWN_MODS_PATH.to_string(),
+        passed,
+        details,
+    }
+}
+
+/// Names of the fields of the `MI_Mod` struct and of the values of its default instance.
+fn mod_struct_fields<R: Read + Seek>(asset: &Asset<R>) -> Option<(Vec<String>, Vec<String>)> {
+    asset.asset_data.exports.iter().find_map(|e| match e {
+        Export::UserDefinedStructExport(e) => Some((
+            e.struct_export
+                .loaded_properties
+                .iter()
+                .filter_map(|p| match p {
+                    FProperty::FGenericProperty(p) => Some(p.name.get_owned_content()),
+                    FProperty::FArrayProperty(p) => {
+                        Some(p.generic_property.name.get_owned_content())
+                    }
+                    _ => None,
+                })
+                .collect(),
+            e.default_struct_instance
+                .iter()
+                .filter_map(|p| match p {
+                    Property::StrProperty(p) => Some(p.name.get_owned_content()),
+                    Property::IntProperty(p) => Some(p.name.get_owned_content()),
+                    Property::ArrayProperty(p) => Some(p.name.get_owned_content()),
+                    _ => None,
+                })
+                .collect(),
+        )),
+        _ => None,
+    })
+}
+
+fn check_mod_struct<R: Read + Seek>(pak: &repak::PakReader, reader: &mut R) -> SelftestCheck {
+    let mut details = vec![];
+    let result = (|| -> Result<bool> {
+        let mut asset = read_asset(pak, reader, MOD_STRUCT_PATH)?;
+        extend_mod_struct(&mut asset)?;
+        details.push("added fields to MI_Mod".to_string());
+
+        // the game has to be able to read the fields back from the written asset
+        let (uasset, uexp) = write_asset(&asset)?;
+        let asset = Asset::new(
+            Cursor::new(uasset),
+            Some(Cursor::new(uexp)),
+            EngineVersion::VER_UE4_27,
+            None,
+        )
+        .context("failed to parse extended MI_Mod")?;
+        let (fields, defaults) =
+            mod_struct_fields(&asset).context("MI_Mod struct not found after writing")?;
+
+        let mut passed = true;
+        for field in MOD_STRUCT_FIELDS {
+            let name = field.split('_').next().unwrap();
+            for (names, kind) in [(&fields, "field"), (&defaults, "default value")] {
+                let found = names.iter().any(|n| n == field);
+                details.push(format!(
+                    "{} {name} {kind}",
+                    if found { "found" } else { "missing" }
+                ));
+                passed &= found;
+            }
+        }
+        Ok(passed)
+    })();
+
+    let passed = match result {
+        Ok(passed) => passed,
+        Err(e) => {
+            details.push(format!("{e:#}"));
+            false
+        }
+    };
+    SelftestCheck {
+        name: "mod struct fields".to_string(),
+        asset: MOD_STRUCT_PATH.to_string(),
+        passed,
+        details,
+    }
+}
+
+/// Runs every patch and integration step against the game pak at `path_pak` without writing
+/// anything. Disabled patches are checked as well.
+pub fn selftest<P: AsRef<Path>>(path_pak: P) -> Result<Vec<SelftestCheck>> {
+    let mut fsd_pak_reader = BufReader::new(fs::File::open(path_pak.as_ref())?);
+    let fsd_pak = repak::PakBuilder::new().reader(&mut fsd_pak_reader)?;
+
+    let mut checks = vec![];
+    for patch in IntegrationPatch::iter() {
+        for path in patch.asset_paths() {
+            checks.push(check_patch(patch, &fsd_pak, &mut fsd_pak_reader, path));
+        }
+    }
+
+    let mut int_pak_reader = Cursor::new(INTEGRATION_PAK);
+    let int_pak = repak::PakBuilder::new().reader(&mut int_pak_reader)?;
+    checks.push(check_spawn_mods(&int_pak, &mut int_pak_reader));
+    checks.push(check_mod_struct(&int_pak, &mut int_pak_reader));
+
+    Ok(checks)
+}
@@ -0,0 +1,136 @@
+//! Output locations of the mod bundle. By default the bundle is installed into the game
+//! installation the game pak belongs to, but it can also be written to a directory or zip laid out
+//! like a game installation, to prepare bundles for dedicated servers or other machines.
+
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use fs_err as fs;
+use mint_lib::{DRGInstallation, DRGInstallationType};
+
+/// Where the mod bundle is written to.
+#[derive(Debug, Clone, Default)]
+pub enum TargetOutput {
+    /// The game installation containing the game pak.
+    #[default]
+    Installation,
+    /// A directory that can be copied over the root of a game installation.
+    Directory(PathBuf),
+    /// A zip archive with the same contents as [`TargetOutput::Directory`].
+    Zip(PathBuf),
+}
+
+/// Which parts of the bundle are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TargetPreset {
+    /// Everything needed to play with mods.
+    #[default]
+    Client,
+    /// Only what a dedicated server needs, skipping the client-only hook DLL.
+    Server,
+}
+
+impl TargetPreset {
+    pub fn includes_hook(self) -> bool {
+        match self {
+            TargetPreset::Client => true,
+            TargetPreset::Server => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BuildTarget {
+    pub output: TargetOutput,
+    pub preset: TargetPreset,
+}
+
+/// Paths the bundle is written to, resolved from a [`BuildTarget`].
+pub(super) struct Layout {
+    pub mod_pak: PathBuf,
+    pub hook_dll: Option<PathBuf>,
+    /// Directory the files are written to before being zipped.
+    staging: Option<(tempfile::TempDir, PathBuf)>,
+}
+
+impl BuildTarget {
+    pub(super) fn layout(&self, game_pak: &Path) -> Result<Layout> {
+        let installation_type = DRGInstallationType::from_pak_path(game_pak)?;
+        let (installation, staging) = match &self.output {
+            TargetOutput::Installation => (DRGInstallation::from_pak_path(game_pak)?, None),
+            TargetOutput::Directory(path) => (
+                DRGInstallation {
+                    root: path.join("FSD"),
+                    installation_type,
+                },
+                None,
+            ),
+            TargetOutput::Zip(path) => {
+                let dir = tempfile::tempdir().context("failed to create staging directory")?;
+                (
+                    DRGInstallation {
+                        root: dir.path().join("FSD"),
+                        installation_type,
+                    },
+                    Some((dir, path.clone())),
+                )
+            }
+        };
+
+        let hook_dll = self.preset.includes_hook().then(|| {
+            installation
+                .binaries_directory()
+                .join(installation.installation_type.hook_dll_name())
+        });
+
+        if !matches!(self.output, TargetOutput::Installation) {
+            fs::create_dir_all(installation.paks_path())?;
+            if hook_dll.is_some() {
+                fs::create_dir_all(installation.binaries_directory())?;
+            }
+        }
+
+        Ok(Layout {
+            mod_pak: installation.paks_path().join("mods_P.pak"),
+            hook_dll,
+            staging,
+        })
+    }
+}
+
+impl Layout {
+    /// Packs the staged files if the target is a zip.
+    pub(super) fn finish(self) -> Result<()> {
+        if let Some((dir, path)) = self.staging {
+            zip_directory(dir.path(), &path)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+fn zip_directory(dir: &Path, out: &Path) -> Result<()> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(d) = dirs.pop() {
+        for entry in fs::read_dir(&d)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+
+    let mut zip = zip::ZipWriter::new(BufWriter::new(fs::File::create(out)?));
+    for path in files {
+        let name = path.strip_prefix(dir)?.to_string_lossy().replace('\\', "/");
+        zip.start_file(name, zip::write::FileOptions::default())?;
+        io::copy(&mut fs::File::open(&path)?, &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
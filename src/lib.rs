@@ -1,27 +1,29 @@
 #![feature(let_chains)]
 
 pub mod error;
+pub mod game_pak_index;
 pub mod gui;
 pub mod integrate;
 pub mod mod_lints;
 pub mod providers;
 pub mod state;
 
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Read, Seek};
 use std::str::FromStr;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 
 use directories::ProjectDirs;
 use error::IntegrationError;
 use fs_err as fs;
-use integrate::IntegrationErr;
-use providers::{ModResolution, ModSpecification, ProviderFactory, ReadSeek};
-use state::State;
+use integrate::{IntegrationErr, IntegrationOptions, IntegrationReport};
+use mint_lib::GameVersion;
+use providers::{ModInfo, ModResolution, ModSpecification, ModStore, ProviderFactory, ReadSeek};
+use state::{ModConfig, State};
 use tracing::{info, warn};
 
 #[derive(Debug)]
@@ -78,15 +80,62 @@ pub fn is_drg_pak<P: AsRef<Path>>(path: P) -> Result<()> {
     Ok(())
 }
 
+/// Resolves `mod_specs` like [`ModStore::resolve_mods`]. With
+/// [`IntegrationOptions::prefer_compatible_modfiles`], unpinned mods whose latest version isn't
+/// made for the game at `game_path` resolve to their newest version that is.
+pub async fn resolve_mods_for_game<P: AsRef<Path>>(
+    store: &ModStore,
+    game_path: P,
+    mod_specs: &[ModSpecification],
+    update: bool,
+    options: &IntegrationOptions,
+) -> Result<HashMap<ModSpecification, ModInfo>> {
+    let mut mods = store.resolve_mods(mod_specs, update).await?;
+    if !options.prefer_compatible_modfiles {
+        return Ok(mods);
+    }
+    let game_version = match GameVersion::from_pak_path(game_path) {
+        Ok(game_version) => game_version,
+        Err(e) => {
+            warn!("not looking for compatible modfiles, failed to read game version: {e:#}");
+            return Ok(mods);
+        }
+    };
+
+    let compatible = mod_specs
+        .iter()
+        .filter_map(|spec| Some((spec, store.compatible_version(spec, &game_version)?)))
+        .collect::<Vec<_>>();
+    if compatible.is_empty() {
+        return Ok(mods);
+    }
+    let pinned = compatible
+        .iter()
+        .map(|(_, pinned)| pinned.clone())
+        .collect::<Vec<_>>();
+    let resolved = store.resolve_mods(&pinned, false).await?;
+    for (spec, pinned) in compatible {
+        info!(
+            "using {} for {} as its latest version isn't made for {game_version}",
+            pinned.url, spec.url
+        );
+        mods.insert(spec.clone(), resolved[&pinned].clone());
+    }
+    Ok(mods)
+}
+
 pub async fn resolve_unordered_and_integrate<P: AsRef<Path>>(
     game_path: P,
     state: &State,
-    mod_specs: &[ModSpecification],
+    mod_configs: &[ModConfig],
     update: bool,
-) -> Result<(), IntegrationErr> {
-    let mods = state
-        .store
-        .resolve_mods(mod_specs, update)
+    options: &IntegrationOptions,
+) -> Result<IntegrationReport, IntegrationErr> {
+    let mod_specs = mod_configs
+        .iter()
+        .map(|mc| mc.spec.clone())
+        .collect::<Vec<_>>();
+    let mods = resolve_mods_for_game(&state.store, &game_path, &mod_specs, update, options)
         .await
         .map_err(|e| IntegrationErr {
             mod_ctxt: None,
@@ -134,7 +183,16 @@ pub async fn resolve_unordered_and_integrate<P: AsRef<Path>>(
             kind: integrate::IntegrationErrKind::Generic(e),
         })?;
 
-    integrate::integrate(game_path, to_integrate.into_iter().zip(paths).collect())
+    integrate::integrate(
+        game_path,
+        to_integrate
+            .into_iter()
+            .zip(mod_configs.iter().cloned())
+            .zip(paths)
+            .map(|((info, config), path)| (info, config, path))
+            .collect(),
+        options,
+    )
 }
 
 async fn resolve_into_urls<'b>(
@@ -186,17 +244,19 @@ pub async fn resolve_ordered(
 pub async fn resolve_unordered_and_integrate_with_provider_init<P, F>(
     game_path: P,
     state: &mut State,
-    mod_specs: &[ModSpecification],
+    mod_configs: &[ModConfig],
     update: bool,
+    options: &IntegrationOptions,
     init: F,
-) -> Result<()>
+) -> Result<IntegrationReport>
 where
     P: AsRef<Path>,
     F: Fn(&mut State, String, &ProviderFactory) -> Result<()>,
 {
     loop {
-        match resolve_unordered_and_integrate(&game_path, state, mod_specs, update).await {
-            Ok(()) => return Ok(()),
+        match resolve_unordered_and_integrate(&game_path, state, mod_configs, update, options).await
+        {
+            Ok(report) => return Ok(report),
             Err(IntegrationErr { mod_ctxt, kind }) => match kind {
                 integrate::IntegrationErrKind::Generic(e) => match e.downcast::<IntegrationError>()
                 {
@@ -250,30 +310,62 @@ where
     }
 }
 
-pub(crate) fn get_pak_from_data(mut data: Box<dyn ReadSeek>) -> Result<Box<dyn ReadSeek>> {
+/// Returns every pak contained in `data` for which `filter` returns `true`, ordered by their path
+/// inside the archive. If `data` is not an archive it is returned as the only pak, without a name.
+pub(crate) fn get_paks_from_data<F: FnMut(&str) -> bool>(
+    mut data: Box<dyn ReadSeek>,
+    mut filter: F,
+) -> Result<Vec<(Option<String>, Box<dyn ReadSeek>)>> {
     if let Ok(mut archive) = zip::ZipArchive::new(&mut data) {
-        (0..archive.len())
-            .map(|i| -> Result<Option<Box<dyn ReadSeek>>> {
-                let mut file = archive.by_index(i)?;
-                match file.enclosed_name() {
-                    Some(p) => {
-                        if file.is_file() && p.extension().filter(|e| e == &"pak").is_some() {
-                            let mut buf = vec![];
-                            file.read_to_end(&mut buf)?;
-                            Ok(Some(Box::new(Cursor::new(buf))))
-                        } else {
-                            Ok(None)
-                        }
-                    }
-                    None => Ok(None),
-                }
-            })
-            .find_map(Result::transpose)
-            .context("zip does not contain pak")?
+        let names = list_paks_in_archive(&mut archive)?;
+        if names.is_empty() {
+            bail!("zip does not contain pak");
+        }
+
+        let mut paks: Vec<(Option<String>, Box<dyn ReadSeek>)> = vec![];
+        for (i, name) in names {
+            if !filter(&name) {
+                continue;
+            }
+            let mut file = archive.by_index(i)?;
+            let mut buf = vec![];
+            file.read_to_end(&mut buf)?;
+            paks.push((Some(name), Box::new(Cursor::new(buf))));
+        }
+        Ok(paks)
     } else {
         data.rewind()?;
-        Ok(data)
+        Ok(vec![(None, data)])
+    }
+}
+
+/// Returns the paths of all paks inside `data` ordered by path, or an empty list if `data` is not
+/// an archive.
+pub fn list_paks_in_data(mut data: Box<dyn ReadSeek>) -> Result<Vec<String>> {
+    if let Ok(mut archive) = zip::ZipArchive::new(&mut data) {
+        Ok(list_paks_in_archive(&mut archive)?
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect())
+    } else {
+        Ok(vec![])
+    }
+}
+
+fn list_paks_in_archive<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<Vec<(usize, String)>> {
+    let mut paks = vec![];
+    for i in 0..archive.len() {
+        let file = archive.by_index(i)?;
+        if let Some(p) = file.enclosed_name() {
+            if file.is_file() && p.extension().filter(|e| e == &"pak").is_some() {
+                paks.push((i, p.to_string_lossy().replace('\\', "/")));
+            }
+        }
     }
+    paks.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(paks)
 }
 
 pub(crate) enum PakOrNotPak {
@@ -283,7 +375,8 @@ pub(crate) enum PakOrNotPak {
 
 pub(crate) enum GetAllFilesFromDataError {
     EmptyArchive,
-    OnlyNonPakFiles,
+    /// Carries the files of the archive, none of which is a pak.
+    OnlyNonPakFiles(Vec<(PathBuf, PakOrNotPak)>),
     Other(anyhow::Error),
 }
 
@@ -332,7 +425,7 @@ pub(crate) fn lint_get_all_files_from_data(
         {
             Ok(files)
         } else {
-            Err(GetAllFilesFromDataError::OnlyNonPakFiles)
+            Err(GetAllFilesFromDataError::OnlyNonPakFiles(files))
         }
     } else {
         data.rewind()
@@ -1,21 +1,84 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::io::BufWriter;
 use std::path::PathBuf;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::{Parser, Subcommand};
 use fs_err as fs;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::filter;
 
-use mint::mod_lints::{run_lints, LintId};
+use mint::integrate::{
+    modio_ini_backups, plan_modio_ini_update, restore_modio_ini, selftest, BuildTarget,
+    BundleCompression, DiffLine, IntegrationOptions, TargetOutput, TargetPreset,
+};
+use mint::mod_lints::{fix_mods, run_lints_with_ctxt, LintCtxt, LintDefinition, LintId};
 use mint::providers::ProviderFactory;
-use mint::{gui::gui, providers::ModSpecification, state::State};
+use mint::{
+    gui::gui,
+    providers::ModSpecification,
+    state::{ModConfig, State},
+};
 use mint::{
     resolve_ordered_with_provider_init, resolve_unordered_and_integrate_with_provider_init, Dirs,
 };
 
+/// Options for building the mod bundle, overriding the config.
+#[derive(clap::Args, Debug)]
+struct BundleArgs {
+    /// Compression of the generated mod bundle. Defaults to the value in the config.
+    #[arg(short, long, value_enum)]
+    compression: Option<BundleCompression>,
+
+    /// Merge rows of DataTables modified by several mods, or only use the version of the mod with
+    /// the highest priority with `--merge-datatables=false`. Defaults to the value in the config.
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    merge_datatables: Option<bool>,
+
+    /// Use the newest version named after the installed game version of mod.io mods whose latest
+    /// version isn't made for it. Defaults to the value in the config.
+    #[arg(long, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    prefer_compatible_modfiles: Option<bool>,
+
+    /// Write the bundle to a directory laid out like the game installation instead of
+    /// installing it.
+    #[arg(long, value_name = "DIR", conflicts_with = "out_zip")]
+    out_dir: Option<PathBuf>,
+
+    /// Write the bundle to a zip laid out like the game installation instead of installing it.
+    #[arg(long, value_name = "ZIP")]
+    out_zip: Option<PathBuf>,
+
+    /// Parts of the bundle to write. The server preset leaves out the hook DLL.
+    #[arg(long, value_enum, default_value_t)]
+    preset: TargetPreset,
+}
+
+impl BundleArgs {
+    fn options(&self, state: &State) -> IntegrationOptions {
+        let mut options = state.config.integration_options();
+        if let Some(compression) = self.compression {
+            options.compression = compression;
+        }
+        if let Some(merge) = self.merge_datatables {
+            options.merge_datatables = merge;
+        }
+        if let Some(prefer) = self.prefer_compatible_modfiles {
+            options.prefer_compatible_modfiles = prefer;
+        }
+        options.target = BuildTarget {
+            output: match (&self.out_dir, &self.out_zip) {
+                (Some(dir), _) => TargetOutput::Directory(dir.clone()),
+                (_, Some(zip)) => TargetOutput::Zip(zip.clone()),
+                _ => TargetOutput::Installation,
+            },
+            preset: self.preset,
+        };
+        options
+    }
+}
+
 /// Command line integration tool.
 #[derive(Parser, Debug)]
 struct ActionIntegrate {
@@ -30,6 +93,9 @@ struct ActionIntegrate {
     #[arg(short, long)]
     update: bool,
 
+    #[command(flatten)]
+    bundle: BundleArgs,
+
     /// Paths of mods to integrate
     ///
     /// Can be a file path or URL to a .pak or .zip file or a URL to a mod on https://mod.io/g/drg
@@ -55,6 +121,9 @@ struct ActionIntegrateProfile {
     #[arg(short, long)]
     update: bool,
 
+    #[command(flatten)]
+    bundle: BundleArgs,
+
     /// Profile to integrate.
     profile: String,
 }
@@ -74,16 +143,128 @@ struct ActionLint {
     #[arg(short, long)]
     fsd_pak: Option<PathBuf>,
 
+    /// Lints to run. Defaults to every lint that doesn't compare mods against the game pak.
+    #[arg(short, long = "lint", value_name = "LINT", value_parser = parse_lint_id)]
+    lints: Vec<LintId>,
+
+    /// Write copies of the mod paks without the files reported by fixable lints to the cache.
+    #[arg(long)]
+    fix: bool,
+
+    /// Integrate the cleaned copies instead of the mods' own files. The mods keep their
+    /// specifications, settings and suppressions, and go back to their own files once updated.
+    #[arg(long, requires = "fix")]
+    use_fixed: bool,
+
+    /// Save the findings as the baseline of the profile. Findings not in the baseline are marked
+    /// as new.
+    #[arg(long)]
+    save_baseline: bool,
+
+    /// Write a Markdown summary of the findings to this file.
+    #[arg(long, value_name = "PATH")]
+    markdown: Option<PathBuf>,
+
+    /// Write an HTML report of the findings to this file.
+    #[arg(long, value_name = "PATH")]
+    html: Option<PathBuf>,
+
     /// Profile to lint.
     profile: String,
 }
 
+fn parse_lint_id(s: &str) -> Result<LintId, String> {
+    LintId::from_name(s).ok_or_else(|| {
+        format!(
+            "unknown lint `{s}`, expected one of: {}",
+            LintDefinition::all()
+                .iter()
+                .map(|l| l.id.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    })
+}
+
+/// Check whether mint's patches still apply to the installed game, without changing anything.
+#[derive(Parser, Debug)]
+struct ActionSelftest {
+    /// Path to FSD-WindowsNoEditor.pak (FSD-WinGDK.pak for Microsoft Store version) located
+    /// inside the "Deep Rock Galactic" installation directory under FSD/Content/Paks. Only
+    /// necessary if it cannot be found automatically.
+    #[arg(short, long)]
+    fsd_pak: Option<PathBuf>,
+}
+
+/// Show or change the settings passed to a mod in game.
+#[derive(Parser, Debug)]
+struct ActionModSettings {
+    /// Profile containing the mod.
+    profile: String,
+
+    /// URL or path of the mod, as shown in the profile.
+    #[arg(value_name = "MOD")]
+    mod_url: String,
+
+    /// Settings to set, as KEY=VALUE.
+    #[arg(value_parser = parse_mod_setting)]
+    set: Vec<(String, String)>,
+
+    /// Settings to remove.
+    #[arg(long, value_name = "KEY")]
+    unset: Vec<String>,
+}
+
+/// Manage the config of the official mod.io integration, which mint leaves alone unless asked.
+#[derive(Parser, Debug)]
+struct ActionModioConfig {
+    /// Path to FSD-WindowsNoEditor.pak (FSD-WinGDK.pak for Microsoft Store version) located
+    /// inside the "Deep Rock Galactic" installation directory under FSD/Content/Paks. Only
+    /// necessary if it cannot be found automatically.
+    #[arg(short, long)]
+    fsd_pak: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: ModioConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum ModioConfigCommand {
+    /// Show how the config would change to disable mods installed through mod.io and enable the
+    /// mods of a profile instead.
+    Preview {
+        /// Profile whose mod.io mods are enabled.
+        profile: String,
+    },
+    /// Back up the config and apply the changes shown by `preview`.
+    Apply {
+        /// Profile whose mod.io mods are enabled.
+        profile: String,
+    },
+    /// List backups of the config, newest first.
+    Backups,
+    /// Restore a backup of the config. The current config is backed up first.
+    Restore {
+        /// Backup to restore. Defaults to the newest one.
+        backup: Option<PathBuf>,
+    },
+}
+
+fn parse_mod_setting(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got `{s}`"))
+}
+
 #[derive(Subcommand, Debug)]
 enum Action {
     Integrate(ActionIntegrate),
     Profile(ActionIntegrateProfile),
     Launch(ActionLaunch),
     Lint(ActionLint),
+    ModSettings(ActionModSettings),
+    ModioConfig(ActionModioConfig),
+    Selftest(ActionSelftest),
 }
 
 #[derive(Parser, Debug)]
@@ -147,6 +328,9 @@ fn main() -> Result<()> {
             action_lint(dirs, action).await?;
             Ok(())
         }),
+        Some(Action::ModSettings(action)) => action_mod_settings(dirs, action),
+        Some(Action::ModioConfig(action)) => action_modio_config(dirs, action),
+        Some(Action::Selftest(action)) => action_selftest(dirs, action),
         None => {
             std::thread::spawn(move || {
                 rt.block_on(std::future::pending::<()>());
@@ -251,20 +435,24 @@ async fn action_integrate(dirs: Dirs, action: ActionIntegrate) -> Result<()> {
     let game_pak_path = get_pak_path(&state, &action.fsd_pak)?;
     debug!(?game_pak_path);
 
-    let mod_specs = action
+    let mod_configs = action
         .mods
         .into_iter()
-        .map(ModSpecification::new)
+        .map(|url| ModConfig::new(ModSpecification::new(url)))
         .collect::<Vec<_>>();
 
+    let options = action.bundle.options(&state);
+
     resolve_unordered_and_integrate_with_provider_init(
         game_pak_path,
         &mut state,
-        &mod_specs,
+        &mod_configs,
         action.update,
+        &options,
         init_provider,
     )
-    .await
+    .await?;
+    Ok(())
 }
 
 async fn action_integrate_profile(dirs: Dirs, action: ActionIntegrateProfile) -> Result<()> {
@@ -274,17 +462,140 @@ async fn action_integrate_profile(dirs: Dirs, action: ActionIntegrateProfile) ->
 
     let mut mods = Vec::new();
     state.mod_data.for_each_enabled_mod(&action.profile, |mc| {
-        mods.push(mc.spec.clone());
+        mods.push(mc.clone());
     });
 
+    let mut options = action.bundle.options(&state);
+    options.disabled_patches = state.mod_data.profiles[&action.profile]
+        .disabled_patches
+        .clone();
+
     resolve_unordered_and_integrate_with_provider_init(
         game_pak_path,
         &mut state,
         &mods,
         action.update,
+        &options,
         init_provider,
     )
-    .await
+    .await?;
+    Ok(())
+}
+
+fn action_mod_settings(dirs: Dirs, action: ActionModSettings) -> Result<()> {
+    let mut state = State::init(dirs)?;
+    if !state.mod_data.profiles.contains_key(&action.profile) {
+        bail!("profile \"{}\" does not exist", action.profile);
+    }
+
+    let mut found = false;
+    state.mod_data.for_each_mod_mut(&action.profile, |mc| {
+        if mc.spec.url != action.mod_url {
+            return;
+        }
+        found = true;
+        for key in &action.unset {
+            mc.settings.remove(key);
+        }
+        mc.settings.extend(action.set.iter().cloned());
+        for (key, value) in &mc.settings {
+            println!("{key}={value}");
+        }
+    });
+    if !found {
+        bail!(
+            "mod {} is not in profile \"{}\"",
+            action.mod_url,
+            action.profile
+        );
+    }
+
+    state.mod_data.save()?;
+    Ok(())
+}
+
+fn action_modio_config(dirs: Dirs, action: ActionModioConfig) -> Result<()> {
+    let state = State::init(dirs)?;
+    let game_pak_path = get_pak_path(&state, &action.fsd_pak)?;
+    debug!(?game_pak_path);
+
+    let modio_mods = |profile: &str| -> Result<HashSet<u32>> {
+        if !state.mod_data.profiles.contains_key(profile) {
+            bail!("profile \"{profile}\" does not exist");
+        }
+        let mut mods = HashSet::new();
+        state.mod_data.for_each_enabled_mod(profile, |mc| {
+            if let Some(modio_id) = state.store.get_mod_info(&mc.spec).and_then(|i| i.modio_id) {
+                mods.insert(modio_id);
+            }
+        });
+        Ok(mods)
+    };
+
+    match action.command {
+        ModioConfigCommand::Preview { profile } => {
+            match plan_modio_ini_update(&game_pak_path, &modio_mods(&profile)?)? {
+                Some(update) => {
+                    println!("{}", update.path.display());
+                    for line in update.diff() {
+                        match line {
+                            DiffLine::Unchanged(_) => {}
+                            DiffLine::Added(l) => println!("+ {l}"),
+                            DiffLine::Removed(l) => println!("- {l}"),
+                        }
+                    }
+                }
+                None => println!("nothing to change"),
+            }
+        }
+        ModioConfigCommand::Apply { profile } => {
+            match plan_modio_ini_update(&game_pak_path, &modio_mods(&profile)?)? {
+                Some(update) => {
+                    let backup = update.apply()?;
+                    println!(
+                        "backed up {} to {}",
+                        update.path.display(),
+                        backup.display()
+                    );
+                }
+                None => println!("nothing to change"),
+            }
+        }
+        ModioConfigCommand::Backups => {
+            for backup in modio_ini_backups(&game_pak_path)? {
+                println!("{}", backup.display());
+            }
+        }
+        ModioConfigCommand::Restore { backup } => {
+            let backup = match backup {
+                Some(backup) => backup,
+                None => modio_ini_backups(&game_pak_path)?
+                    .into_iter()
+                    .next()
+                    .context("no backups found")?,
+            };
+            restore_modio_ini(&game_pak_path, &backup)?;
+            println!("restored {}", backup.display());
+        }
+    }
+    Ok(())
+}
+
+fn action_selftest(dirs: Dirs, action: ActionSelftest) -> Result<()> {
+    let state = State::init(dirs)?;
+    let game_pak_path = get_pak_path(&state, &action.fsd_pak)?;
+    debug!(?game_pak_path);
+
+    let checks = selftest(&game_pak_path)?;
+    for check in &checks {
+        println!("{check}");
+    }
+    let failed = checks.iter().filter(|c| !c.passed).count();
+    if failed > 0 {
+        bail!("{failed} of {} checks failed", checks.len());
+    }
+    println!("all {} checks passed", checks.len());
+    Ok(())
 }
 
 async fn action_lint(dirs: Dirs, action: ActionLint) -> Result<()> {
@@ -292,31 +603,149 @@ async fn action_lint(dirs: Dirs, action: ActionLint) -> Result<()> {
     let game_pak_path = get_pak_path(&state, &action.fsd_pak)?;
     debug!(?game_pak_path);
 
-    let mut mods = Vec::new();
-    state.mod_data.for_each_mod(&action.profile, |mc| {
-        mods.push(mc.spec.clone());
+    let lints: BTreeSet<LintId> = if action.lints.is_empty() {
+        LintDefinition::all()
+            .into_iter()
+            .filter(|l| !l.requires_game_pak)
+            .map(|l| l.id)
+            .collect()
+    } else {
+        action.lints.into_iter().collect()
+    };
+
+    // in load order, like when integrating
+    let mut mod_configs = Vec::new();
+    state.mod_data.for_each_enabled_mod(&action.profile, |mc| {
+        mod_configs.push(mc.clone());
     });
+    mod_configs.sort_by_key(|k| -k.priority);
+    let mods = mod_configs
+        .iter()
+        .map(|mc| mc.spec.clone())
+        .collect::<Vec<_>>();
+
+    // paks left out when integrating are left out of the lints as well
+    let mut disabled_paks = mod_configs
+        .iter()
+        .filter(|mc| !mc.disabled_paks.is_empty())
+        .map(|mc| (mc.spec.clone(), mc.disabled_paks.clone()))
+        .collect::<HashMap<_, _>>();
+
+    // disabled mods that could provide missing references
+    let mut candidate_mods = Vec::new();
+    if lints.contains(&LintId::UNRESOLVED_REFERENCES) {
+        state.mod_data.for_each_mod(&action.profile, |mc| {
+            if !mods.contains(&mc.spec) && !candidate_mods.contains(&mc.spec) {
+                candidate_mods.push(mc.spec.clone());
+                if !mc.disabled_paks.is_empty() {
+                    disabled_paks.insert(mc.spec.clone(), mc.disabled_paks.clone());
+                }
+            }
+        });
+    }
+
+    // mods cleaned by an earlier fix are linted as they are integrated
+    let mod_paths = resolve_ordered_with_provider_init(&mut state, &mods, init_provider)
+        .await?
+        .into_iter()
+        .zip(&mod_configs)
+        .map(|(path, mc)| mc.file_path(&path))
+        .collect::<Vec<_>>();
+
+    // candidates only improve the report, so failing to fetch them isn't fatal
+    let candidate_pairs = if candidate_mods.is_empty() {
+        vec![]
+    } else {
+        match resolve_ordered_with_provider_init(&mut state, &candidate_mods, init_provider).await {
+            Ok(paths) => candidate_mods.into_iter().zip(paths).collect(),
+            Err(e) => {
+                warn!("failed to resolve candidate mods for linting: {e:#}");
+                vec![]
+            }
+        }
+    };
 
-    let mod_paths = resolve_ordered_with_provider_init(&mut state, &mods, init_provider).await?;
+    let mod_infos = mods
+        .iter()
+        .filter_map(|spec| Some((spec.clone(), state.store.get_mod_info(spec)?)))
+        .collect();
+    let profile = &state.mod_data.profiles[&action.profile];
+    let suppressions = profile.lint_suppressions.clone();
+    let baseline = profile.lint_baseline.clone();
+    let cache_dir = state.dirs.cache_dir.clone();
 
     let report = tokio::task::spawn_blocking(move || {
-        run_lints(
-            &BTreeSet::from([
-                LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES,
-                LintId::ASSET_REGISTRY_BIN,
-                LintId::CONFLICTING,
-                LintId::EMPTY_ARCHIVE,
-                LintId::OUTDATED_PAK_VERSION,
-                LintId::SHADER_FILES,
-                LintId::ARCHIVE_WITH_MULTIPLE_PAKS,
-                LintId::NON_ASSET_FILES,
-                LintId::SPLIT_ASSET_PAIRS,
-            ]),
+        let lint_ctxt = LintCtxt::init(
             mods.into_iter().zip(mod_paths).collect(),
             Some(game_pak_path),
-        )
+        )?
+        .with_candidate_mods(candidate_pairs.into_iter().collect())
+        .with_disabled_paks(disabled_paks)
+        .with_mod_infos(mod_infos)
+        .with_suppressions(suppressions)
+        .with_cache_dir(cache_dir);
+        run_lints_with_ctxt(&lints, &lint_ctxt)
     })
     .await??;
-    println!("{:#?}", report);
+    for finding in &report.findings {
+        println!(
+            "{}[{}]{}: {}",
+            finding.severity.as_str(),
+            finding.lint,
+            if finding.is_new(baseline.as_ref()) {
+                " (new)"
+            } else {
+                ""
+            },
+            finding.message
+        );
+        if finding.mods.len() > 1 {
+            for mod_spec in &finding.mods {
+                println!("    {}", mod_spec.url);
+            }
+        }
+    }
+    if !report.suppressed.is_empty() {
+        println!("{} findings suppressed", report.suppressed.len());
+    }
+
+    if let Some(path) = &action.markdown {
+        fs::write(path, report.to_markdown())?;
+    }
+    if let Some(path) = &action.html {
+        fs::write(path, report.to_html())?;
+    }
+
+    if action.save_baseline {
+        state
+            .mod_data
+            .profiles
+            .get_mut(&action.profile)
+            .unwrap()
+            .lint_baseline = Some(report.baseline());
+        state.mod_data.save()?;
+        info!("saved lint baseline of profile `{}`", action.profile);
+    }
+
+    if action.fix {
+        let fixed_mods = fix_mods(&report, &state.store)?;
+        for fixed in &fixed_mods {
+            println!(
+                "removed {} files from {}, cleaned pak written to {}",
+                fixed.removed.len(),
+                fixed.mod_spec.url,
+                fixed.path.display()
+            );
+        }
+        if action.use_fixed && !fixed_mods.is_empty() {
+            state.mod_data.for_each_mod_mut(&action.profile, |mc| {
+                if let Some(fixed) = fixed_mods.iter().find(|f| f.mod_spec == mc.spec) {
+                    mc.use_fixed_pak(fixed.path.clone(), &fixed.source);
+                }
+            });
+            state.mod_data.save()?;
+            info!("profile `{}` now uses the cleaned paks", action.profile);
+        }
+    }
     Ok(())
 }
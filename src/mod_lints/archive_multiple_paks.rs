@@ -4,7 +4,23 @@ use anyhow::Result;
 
 use crate::providers::ModSpecification;
 
-use super::{Lint, LintCtxt};
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const ARCHIVE_WITH_MULTIPLE_PAKS: Self = LintId::new("archive_with_multiple_paks");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::ARCHIVE_WITH_MULTIPLE_PAKS,
+        title: "Archive with multiple paks",
+        description: "Archives containing more than one `.pak`. All enabled `.pak`s are loaded in order of their path, which may not be what the author intended.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
 
 #[derive(Default)]
 pub struct ArchiveMultiplePaksLint;
@@ -25,3 +41,17 @@ impl Lint for ArchiveMultiplePaksLint {
         Ok(archive_multiple_paks_mods)
     }
 }
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(ArchiveMultiplePaksLint
+            .check_mods(lcx)?
+            .into_iter()
+            .map(|mod_spec| {
+                let message = format!(
+                    "{} contains multiple `.pak`s, all enabled `.pak`s will be loaded in order of their path",
+                    mod_spec.url
+                );
+                LintFinding::new(LintId::ARCHIVE_WITH_MULTIPLE_PAKS, mod_spec, None, message)
+            })
+            .collect())
+}
@@ -2,7 +2,23 @@ use std::collections::BTreeSet;
 
 use crate::providers::ModSpecification;
 
-use super::{Lint, LintCtxt};
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const ARCHIVE_WITH_ONLY_NON_PAK_FILES: Self = LintId::new("archive_only_non_pak_files");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES,
+        title: "Archive with only non-pak files",
+        description: "Archives without any `.pak`, usually unpacked assets the author forgot to pack.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
 
 #[derive(Default)]
 pub struct ArchiveOnlyNonPakFilesLint;
@@ -23,3 +39,22 @@ impl Lint for ArchiveOnlyNonPakFilesLint {
         Ok(archive_only_non_pak_files_mods)
     }
 }
+
+fn findings(lcx: &LintCtxt) -> anyhow::Result<Vec<LintFinding>> {
+    Ok(ArchiveOnlyNonPakFilesLint
+        .check_mods(lcx)?
+        .into_iter()
+        .map(|mod_spec| {
+            let message = format!(
+                "{} contains only non-`.pak` files, perhaps the author forgot to pack it?",
+                mod_spec.url
+            );
+            LintFinding::new(
+                LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES,
+                mod_spec,
+                None,
+                message,
+            )
+        })
+        .collect())
+}
@@ -3,7 +3,23 @@ use std::collections::{BTreeMap, BTreeSet};
 
 use crate::providers::ModSpecification;
 
-use super::{Lint, LintCtxt};
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const ASSET_REGISTRY_BIN: Self = LintId::new("asset_registry_bin");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::ASSET_REGISTRY_BIN,
+        title: "Mods containing AssetRegistry.bin",
+        description: "Mods shipping an `AssetRegistry.bin`, which is skipped when integrating.",
+        severity: Severity::Info,
+        requires_game_pak: false,
+        fixable: true,
+        check: findings,
+    }
+}
 
 #[derive(Default)]
 pub struct AssetRegisterBinLint;
@@ -32,3 +48,21 @@ impl Lint for AssetRegisterBinLint {
         Ok(asset_register_bin_mods)
     }
 }
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(AssetRegisterBinLint
+        .check_mods(lcx)?
+        .into_iter()
+        .flat_map(|(mod_spec, paths)| {
+            paths.into_iter().map(move |path| {
+                let message = format!("{} includes `{path}`", mod_spec.url);
+                LintFinding::new(
+                    LintId::ASSET_REGISTRY_BIN,
+                    mod_spec.clone(),
+                    Some(path),
+                    message,
+                )
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,117 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use crate::providers::ModSpecification;
+
+use super::{
+    resolve_path, Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity, UnresolvablePath,
+    STANDARD_MOUNT_POINT,
+};
+
+impl LintId {
+    pub const BAD_PATHS: Self = LintId::new("bad_paths");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::BAD_PATHS,
+        title: "Mods with bad mount points or paths",
+        description: "Paks mounted somewhere other than the game root, and files that end up outside the game directory or outside `FSD/` where the game doesn't load them.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
+
+#[derive(Default)]
+pub struct BadPathsLint;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BadPath {
+    /// The pak isn't mounted at `../../../`, carrying the mount point.
+    MountPoint(String),
+    /// An entry path that can't be placed in the game directory, as stored in the pak.
+    Unresolvable(String, UnresolvablePath),
+    /// A path relative to the game root that isn't in `FSD/`.
+    OutsideFsd(String),
+}
+
+impl Lint for BadPathsLint {
+    type Output = BTreeMap<ModSpecification, Vec<BadPath>>;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output> {
+        let mut bad_paths_mods = BTreeMap::new();
+
+        lcx.for_each_mod(
+            |mod_spec, _, pak_reader| {
+                let mount = pak_reader.mount_point();
+                let mut bad_paths = vec![];
+                if mount != STANDARD_MOUNT_POINT {
+                    bad_paths.push(BadPath::MountPoint(mount.to_string()));
+                }
+                for p in pak_reader.files() {
+                    match resolve_path(mount, &p) {
+                        Ok(path) => {
+                            if !path.to_ascii_lowercase().starts_with("fsd/") {
+                                bad_paths.push(BadPath::OutsideFsd(path));
+                            }
+                        }
+                        Err(e) => bad_paths.push(BadPath::Unresolvable(p, e)),
+                    }
+                }
+                if !bad_paths.is_empty() {
+                    let mod_bad_paths: &mut Vec<_> = bad_paths_mods.entry(mod_spec).or_default();
+                    mod_bad_paths.extend(bad_paths);
+                    mod_bad_paths.sort();
+                }
+                Ok(())
+            },
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+        )?;
+
+        Ok(bad_paths_mods)
+    }
+}
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(BadPathsLint
+        .check_mods(lcx)?
+        .into_iter()
+        .flat_map(|(mod_spec, bad_paths)| {
+            bad_paths.into_iter().map(move |bad_path| {
+                let url = &mod_spec.url;
+                let (severity, path, message) = match bad_path {
+                    BadPath::MountPoint(mount) => (
+                        Severity::Warning,
+                        None,
+                        format!(
+                            "{url} is mounted at `{mount}` instead of `{STANDARD_MOUNT_POINT}`"
+                        ),
+                    ),
+                    BadPath::Unresolvable(path, UnresolvablePath::Absolute) => {
+                        let message = format!("`{path}` in {url} is an absolute path");
+                        (Severity::Error, Some(path), message)
+                    }
+                    BadPath::Unresolvable(path, UnresolvablePath::OutsideGameRoot) => {
+                        let message =
+                            format!("`{path}` in {url} points outside the game directory");
+                        (Severity::Error, Some(path), message)
+                    }
+                    BadPath::OutsideFsd(path) => {
+                        let message =
+                            format!("`{path}` in {url} is outside `FSD/` and won't be loaded");
+                        (Severity::Warning, Some(path.to_ascii_lowercase()), message)
+                    }
+                };
+                LintFinding {
+                    severity,
+                    ..LintFinding::new(LintId::BAD_PATHS, mod_spec.clone(), path, message)
+                }
+            })
+        })
+        .collect())
+}
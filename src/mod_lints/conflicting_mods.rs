@@ -5,7 +5,23 @@ use indexmap::IndexSet;
 
 use crate::providers::ModSpecification;
 
-use super::{Lint, LintCtxt};
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const CONFLICTING: Self = LintId::new("conflicting");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::CONFLICTING,
+        title: "Mods containing conflicting files",
+        description: "Files modified by more than one mod. Only the version of the mod with the highest priority ends up in game.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
 
 #[derive(Default)]
 pub struct ConflictingModsLint;
@@ -44,3 +60,17 @@ impl Lint for ConflictingModsLint {
         Ok(conflicting_mods)
     }
 }
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(ConflictingModsLint
+        .check_mods(lcx)?
+        .into_iter()
+        .map(|(path, mods)| LintFinding {
+            lint: LintId::CONFLICTING,
+            severity: Severity::Warning,
+            message: format!("Conflicting modification of asset `{path}`"),
+            mods: mods.into_iter().collect(),
+            path: Some(path),
+        })
+        .collect())
+}
@@ -0,0 +1,173 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Cursor;
+
+use anyhow::Result;
+use rayon::prelude::*;
+use unreal_asset::{engine_version::EngineVersion, Asset};
+
+use crate::providers::ModSpecification;
+
+use super::{resolve_path, Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const CORRUPT_ASSETS: Self = LintId::new("corrupt_assets");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::CORRUPT_ASSETS,
+        title: "Mods containing corrupt assets",
+        description: "Assets that can't be parsed as UE4.27 assets, which makes integration fail or crashes the game.",
+        severity: Severity::Error,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
+
+#[derive(Default)]
+pub struct CorruptAssetsLint;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CorruptAsset {
+    /// The package summary is from a different engine version.
+    EngineVersion(String),
+    ParseError(String),
+}
+
+/// `FileVersionUE4` of packages saved by UE4.27, `VER_UE4_CORRECT_LICENSEE_FLAG`.
+const UE4_27_FILE_VERSION: i32 = 522;
+const PACKAGE_FILE_TAG: u32 = 0x9E2A83C1;
+
+/// Checks the versions in the package summary, which `unreal_asset` doesn't verify.
+fn check_summary(uasset: &[u8]) -> Option<CorruptAsset> {
+    let read_i32 = |offset: usize| {
+        uasset
+            .get(offset..offset + 4)
+            .map(|b| i32::from_le_bytes(b.try_into().unwrap()))
+    };
+    let Some(tag) = read_i32(0) else {
+        return Some(CorruptAsset::ParseError(
+            "file is too short to be an asset".to_string(),
+        ));
+    };
+    if tag as u32 != PACKAGE_FILE_TAG {
+        return Some(CorruptAsset::ParseError(
+            "missing package file tag".to_string(),
+        ));
+    }
+    let legacy_file_version = read_i32(4)?;
+    if legacy_file_version <= -8 {
+        return Some(CorruptAsset::EngineVersion(
+            "saved with Unreal Engine 5".to_string(),
+        ));
+    }
+    // LegacyUE3Version is only present if the legacy version isn't -4
+    let offset = if legacy_file_version == -4 { 8 } else { 12 };
+    let file_version_ue4 = read_i32(offset)?;
+    // unversioned packages don't record a version at all
+    (file_version_ue4 != 0 && file_version_ue4 != UE4_27_FILE_VERSION).then(|| {
+        CorruptAsset::EngineVersion(format!(
+            "saved with object version {file_version_ue4}, expected {UE4_27_FILE_VERSION} (UE4.27)"
+        ))
+    })
+}
+
+fn check_asset(uasset: &[u8], uexp: &[u8]) -> Option<CorruptAsset> {
+    if let Some(problem) = check_summary(uasset) {
+        return Some(problem);
+    }
+    // malformed assets can make the parser panic instead of returning an error
+    match std::panic::catch_unwind(|| {
+        Asset::new(
+            Cursor::new(uasset),
+            Some(Cursor::new(uexp)),
+            EngineVersion::VER_UE4_27,
+            None,
+        )
+        .err()
+    }) {
+        Ok(e) => e.map(|e| CorruptAsset::ParseError(e.to_string())),
+        Err(_) => Some(CorruptAsset::ParseError("parser panicked".to_string())),
+    }
+}
+
+impl Lint for CorruptAssetsLint {
+    type Output = BTreeMap<ModSpecification, BTreeMap<String, CorruptAsset>>;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output> {
+        let mut corrupt_assets_mods = BTreeMap::new();
+
+        lcx.for_each_mod(
+            |mod_spec, pak_read_seek, pak_reader| {
+                let files = pak_reader.files().into_iter().collect::<HashSet<_>>();
+                let mut pairs = vec![];
+                for uasset_path in &files {
+                    let Some(stem) = uasset_path.strip_suffix(".uasset") else {
+                        continue;
+                    };
+                    let uexp_path = format!("{stem}.uexp");
+                    // split pairs are reported by their own lint
+                    if !files.contains(&uexp_path) {
+                        continue;
+                    }
+                    let Ok(path) = resolve_path(pak_reader.mount_point(), uasset_path) else {
+                        continue;
+                    };
+                    pairs.push((
+                        path.to_ascii_lowercase(),
+                        pak_reader.get(uasset_path, pak_read_seek)?,
+                        pak_reader.get(&uexp_path, pak_read_seek)?,
+                    ));
+                }
+
+                let corrupt = pairs
+                    .par_iter()
+                    .filter_map(|(path, uasset, uexp)| {
+                        check_asset(uasset, uexp).map(|problem| (path.clone(), problem))
+                    })
+                    .collect::<BTreeMap<_, _>>();
+                if !corrupt.is_empty() {
+                    corrupt_assets_mods
+                        .entry(mod_spec)
+                        .or_insert_with(BTreeMap::new)
+                        .extend(corrupt);
+                }
+                Ok(())
+            },
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+        )?;
+
+        Ok(corrupt_assets_mods)
+    }
+}
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(CorruptAssetsLint
+        .check_mods(lcx)?
+        .into_iter()
+        .flat_map(|(mod_spec, files)| {
+            files.into_iter().map(move |(path, problem)| {
+                let message = match problem {
+                    CorruptAsset::EngineVersion(problem) => {
+                        format!(
+                            "`{path}` in {} has the wrong engine version: {problem}",
+                            mod_spec.url
+                        )
+                    }
+                    CorruptAsset::ParseError(e) => {
+                        format!("`{path}` in {} could not be parsed: {e}", mod_spec.url)
+                    }
+                };
+                LintFinding::new(
+                    LintId::CORRUPT_ASSETS,
+                    mod_spec.clone(),
+                    Some(path),
+                    message,
+                )
+            })
+        })
+        .collect())
+}
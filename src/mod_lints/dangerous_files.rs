@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use mint_lib::DRGInstallationType;
+
+use crate::providers::ModSpecification;
+
+use super::non_asset_files::ENDS_WITH_WHITE_LIST;
+use super::{resolve_path, Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const DANGEROUS_FILES: Self = LintId::new("dangerous_files");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::DANGEROUS_FILES,
+        title: "Mods containing executables or scripts",
+        description: "Executables, DLLs and scripts in mod archives or paks, archives nested inside them and large files that aren't assets. None of them are needed by mods and they may be malicious.",
+        severity: Severity::Error,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
+
+/// Non-asset files larger than this are reported.
+const LARGE_FILE_SIZE: usize = 16 * 1024 * 1024;
+
+const SCRIPT_EXTENSIONS: [&str; 13] = [
+    ".bat", ".cmd", ".ps1", ".psm1", ".vbs", ".vbe", ".js", ".jse", ".wsf", ".hta", ".sh", ".py",
+    ".reg",
+];
+
+/// Magic bytes of archive formats.
+const ARCHIVE_MAGICS: [&[u8]; 6] = [
+    b"PK\x03\x04",
+    b"PK\x05\x06",
+    b"Rar!\x1a\x07",
+    b"7z\xbc\xaf\x27\x1c",
+    b"\x1f\x8b",
+    b"MSCF",
+];
+
+#[derive(Default)]
+pub struct DangerousFilesLint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FileLocation {
+    /// Next to the pak in the mod archive.
+    Archive,
+    Pak,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DangerousFile {
+    /// Named like the DLL mint installs as its hook.
+    HookDll,
+    /// A Windows executable or DLL, whatever its extension.
+    Executable,
+    Script,
+    NestedArchive,
+    /// A non-asset file of the given size.
+    LargeFile(usize),
+}
+
+/// Whether `data` is a PE image, checking the `PE` signature the DOS header points to.
+fn is_pe(data: &[u8]) -> bool {
+    let Some(offset) = data
+        .get(0x3c..0x40)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()) as usize)
+    else {
+        return false;
+    };
+    data.starts_with(b"MZ") && data.get(offset..offset + 4) == Some(&b"PE\0\0"[..])
+}
+
+fn classify(normalized_path: &str, data: &[u8]) -> Option<DangerousFile> {
+    let file_name = normalized_path
+        .rsplit('/')
+        .next()
+        .unwrap_or(normalized_path);
+    if [DRGInstallationType::Steam, DRGInstallationType::Xbox]
+        .iter()
+        .any(|t| t.hook_dll_name() == file_name)
+    {
+        return Some(DangerousFile::HookDll);
+    }
+    if is_pe(data) {
+        return Some(DangerousFile::Executable);
+    }
+    if data.starts_with(b"#!")
+        || SCRIPT_EXTENSIONS
+            .iter()
+            .any(|ext| normalized_path.ends_with(ext))
+    {
+        return Some(DangerousFile::Script);
+    }
+    if ARCHIVE_MAGICS.iter().any(|magic| data.starts_with(magic)) {
+        return Some(DangerousFile::NestedArchive);
+    }
+    let is_unreal_asset = ENDS_WITH_WHITE_LIST
+        .iter()
+        .any(|end| normalized_path.ends_with(end));
+    (!is_unreal_asset && data.len() > LARGE_FILE_SIZE)
+        .then_some(DangerousFile::LargeFile(data.len()))
+}
+
+impl Lint for DangerousFilesLint {
+    type Output = BTreeMap<ModSpecification, BTreeMap<(FileLocation, String), DangerousFile>>;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output> {
+        let mut dangerous_files_mods = BTreeMap::<_, BTreeMap<_, _>>::new();
+
+        lcx.for_each_archive_file(|mod_spec, path, data| {
+            let path = path.to_string_lossy().replace('\\', "/");
+            if let Some(problem) = classify(&path.to_ascii_lowercase(), &data) {
+                dangerous_files_mods
+                    .entry(mod_spec)
+                    .or_default()
+                    .insert((FileLocation::Archive, path), problem);
+            }
+            Ok(())
+        })?;
+
+        lcx.for_each_mod(
+            |mod_spec, pak_read_seek, pak_reader| {
+                for p in pak_reader.files() {
+                    let Ok(path) = resolve_path(pak_reader.mount_point(), &p) else {
+                        continue;
+                    };
+                    let normalized_path = path.to_ascii_lowercase();
+                    let data = pak_reader.get(&p, pak_read_seek)?;
+                    if let Some(problem) = classify(&normalized_path, &data) {
+                        dangerous_files_mods
+                            .entry(mod_spec.clone())
+                            .or_default()
+                            .insert((FileLocation::Pak, normalized_path), problem);
+                    }
+                }
+                Ok(())
+            },
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+        )?;
+
+        Ok(dangerous_files_mods)
+    }
+}
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(DangerousFilesLint
+        .check_mods(lcx)?
+        .into_iter()
+        .flat_map(|(mod_spec, files)| {
+            files.into_iter().map(move |((location, path), problem)| {
+                let location = match location {
+                    FileLocation::Archive => "the archive of",
+                    FileLocation::Pak => "the pak of",
+                };
+                let url = &mod_spec.url;
+                let (severity, message) = match problem {
+                    DangerousFile::HookDll => (
+                        Severity::Error,
+                        format!(
+                            "`{path}` in {location} {url} has the name of the DLL mint installs to load mods and could replace it"
+                        ),
+                    ),
+                    DangerousFile::Executable => (
+                        Severity::Error,
+                        format!("`{path}` in {location} {url} is a Windows executable or DLL"),
+                    ),
+                    DangerousFile::Script => (
+                        Severity::Error,
+                        format!("`{path}` in {location} {url} is a script"),
+                    ),
+                    DangerousFile::NestedArchive => (
+                        Severity::Warning,
+                        format!(
+                            "`{path}` in {location} {url} is an archive, whose contents can't be checked"
+                        ),
+                    ),
+                    DangerousFile::LargeFile(size) => (
+                        Severity::Warning,
+                        format!(
+                            "`{path}` in {location} {url} is a {:.1} MiB file that isn't an asset",
+                            size as f64 / (1024.0 * 1024.0)
+                        ),
+                    ),
+                };
+                LintFinding {
+                    severity,
+                    ..LintFinding::new(
+                        LintId::DANGEROUS_FILES,
+                        mod_spec.clone(),
+                        Some(path),
+                        message,
+                    )
+                }
+            })
+        })
+        .collect())
+}
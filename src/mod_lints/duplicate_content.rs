@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use rayon::prelude::*;
+use sha2::Digest;
+
+use crate::providers::ModSpecification;
+
+use super::{resolve_path, Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const DUPLICATE_CONTENT: Self = LintId::new("duplicate_content");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::DUPLICATE_CONTENT,
+        title: "Mods shipping the same content",
+        description: "Identical files shipped by several mods under different paths, such as a bundled library, and mods sharing most of their files, such as re-uploads of another mod.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
+
+/// Share of the files of two mods that must be identical for them to be near-duplicates.
+const NEAR_DUPLICATE_THRESHOLD: f64 = 0.8;
+
+#[derive(Default)]
+pub struct DuplicateContentLint;
+
+#[derive(Debug, Default, Clone)]
+pub struct DuplicateContent {
+    /// Hex SHA-256 of files shipped by more than one mod under different paths, along with the
+    /// lowercase paths in each mod.
+    pub payloads: BTreeMap<String, IndexMap<ModSpecification, BTreeSet<String>>>,
+    /// Pairs of mods along with the share of their distinct files that are identical.
+    pub near_duplicates: Vec<(ModSpecification, ModSpecification, f64)>,
+}
+
+impl Lint for DuplicateContentLint {
+    type Output = DuplicateContent;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output> {
+        let mut mod_hashes = IndexMap::<ModSpecification, HashSet<String>>::new();
+        let mut payloads = BTreeMap::<String, IndexMap<ModSpecification, BTreeSet<String>>>::new();
+
+        lcx.for_each_mod(
+            |mod_spec, pak_read_seek, pak_reader| {
+                let mut files = vec![];
+                for p in pak_reader.files() {
+                    let Ok(path) = resolve_path(pak_reader.mount_point(), &p) else {
+                        continue;
+                    };
+                    files.push((
+                        path.to_ascii_lowercase(),
+                        pak_reader.get(&p, pak_read_seek)?,
+                    ));
+                }
+
+                let hashes = files
+                    .par_iter()
+                    // empty files are identical without being duplicated content
+                    .filter(|(_, data)| !data.is_empty())
+                    .map(|(path, data)| (path.clone(), hex::encode(sha2::Sha256::digest(data))))
+                    .collect::<Vec<_>>();
+
+                for (path, hash) in hashes {
+                    mod_hashes
+                        .entry(mod_spec.clone())
+                        .or_default()
+                        .insert(hash.clone());
+                    payloads
+                        .entry(hash)
+                        .or_default()
+                        .entry(mod_spec.clone())
+                        .or_default()
+                        .insert(path);
+                }
+                Ok(())
+            },
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+        )?;
+
+        let mut duplicate_content = DuplicateContent::default();
+
+        // identical files at the same path are reported by the conflicting files lint
+        duplicate_content.payloads = payloads
+            .into_iter()
+            .filter(|(_, mods)| {
+                mods.len() > 1
+                    && mods
+                        .values()
+                        .flat_map(|paths| paths.iter())
+                        .collect::<HashSet<_>>()
+                        .len()
+                        > 1
+            })
+            .collect();
+
+        let mods = mod_hashes.into_iter().collect::<Vec<_>>();
+        for (i, (a_spec, a_hashes)) in mods.iter().enumerate() {
+            for (b_spec, b_hashes) in &mods[i + 1..] {
+                let shared = a_hashes.intersection(b_hashes).count();
+                let total = a_hashes.len() + b_hashes.len() - shared;
+                let similarity = shared as f64 / total as f64;
+                if similarity >= NEAR_DUPLICATE_THRESHOLD {
+                    duplicate_content.near_duplicates.push((
+                        a_spec.clone(),
+                        b_spec.clone(),
+                        similarity,
+                    ));
+                }
+            }
+        }
+
+        Ok(duplicate_content)
+    }
+}
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    let duplicate_content = DuplicateContentLint.check_mods(lcx)?;
+
+    let near_duplicates =
+        duplicate_content
+            .near_duplicates
+            .into_iter()
+            .map(|(a_spec, b_spec, similarity)| {
+                let message = format!(
+                    "{} and {} share {:.0}% of their files and may be copies of each other",
+                    a_spec.url,
+                    b_spec.url,
+                    similarity * 100.0
+                );
+                LintFinding {
+                    mods: vec![a_spec.clone(), b_spec],
+                    ..LintFinding::new(LintId::DUPLICATE_CONTENT, a_spec, None, message)
+                }
+            });
+
+    let payloads = duplicate_content.payloads.into_values().map(|mods| {
+        let copies = mods
+            .iter()
+            .flat_map(|(mod_spec, paths)| {
+                paths
+                    .iter()
+                    .map(move |path| format!("`{path}` in {}", mod_spec.url))
+            })
+            .collect::<Vec<_>>();
+        let path = mods
+            .values()
+            .next()
+            .and_then(|paths| paths.first())
+            .cloned();
+        let message = format!(
+            "identical files shipped by several mods: {}",
+            copies.join(", ")
+        );
+        let mods = mods.into_keys().collect::<Vec<_>>();
+        let first = mods[0].clone();
+        LintFinding {
+            mods,
+            ..LintFinding::new(LintId::DUPLICATE_CONTENT, first, path, message)
+        }
+    });
+
+    Ok(near_duplicates.chain(payloads).collect())
+}
@@ -4,7 +4,23 @@ use anyhow::Result;
 
 use crate::providers::ModSpecification;
 
-use super::{Lint, LintCtxt};
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const EMPTY_ARCHIVE: Self = LintId::new("empty_archive");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::EMPTY_ARCHIVE,
+        title: "Mods containing empty archives",
+        description: "Archives without any files.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
 
 #[derive(Default)]
 pub struct EmptyArchiveLint;
@@ -27,3 +43,14 @@ impl Lint for EmptyArchiveLint {
         Ok(empty_archive_mods)
     }
 }
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(EmptyArchiveLint
+        .check_mods(lcx)?
+        .into_iter()
+        .map(|mod_spec| {
+            let message = format!("{} contains an empty archive", mod_spec.url);
+            LintFinding::new(LintId::EMPTY_ARCHIVE, mod_spec, None, message)
+        })
+        .collect())
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::providers::ModSpecification;
+
+use super::{LintDefinition, LintFinding, LintReport, Severity};
+
+const HTML_STYLE: &str = "
+body { font-family: sans-serif; max-width: 60em; margin: 2em auto; padding: 0 1em; color: #222; }
+h1, h2 { border-bottom: 1px solid #ccc; }
+code { background: #eee; padding: 0 0.2em; }
+li { margin: 0.3em 0; }
+.error { color: #b00020; }
+.warning { color: #a06000; }
+.info { color: #0060a0; }
+.lint { color: #666; font-size: 0.9em; }
+";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escapes `text` and renders text between backticks as code, like lint messages use it.
+fn message_html(text: &str) -> String {
+    escape_html(text)
+        .split('`')
+        .enumerate()
+        .map(|(i, part)| {
+            if i % 2 == 1 {
+                format!("<code>{part}</code>")
+            } else {
+                part.to_string()
+            }
+        })
+        .collect()
+}
+
+fn lint_title(finding: &LintFinding) -> &'static str {
+    LintDefinition::get(finding.lint).map_or(finding.lint.name(), |lint| lint.title)
+}
+
+impl LintReport {
+    /// Number of reported findings of each severity, most severe first.
+    fn severity_counts(&self) -> Vec<(Severity, usize)> {
+        [Severity::Error, Severity::Warning, Severity::Info]
+            .into_iter()
+            .map(|severity| {
+                let count = self
+                    .findings
+                    .iter()
+                    .filter(|f| f.severity == severity)
+                    .count();
+                (severity, count)
+            })
+            .collect()
+    }
+
+    fn summary(&self) -> String {
+        let mut summary = self
+            .severity_counts()
+            .into_iter()
+            .map(|(severity, count)| {
+                let name = match severity {
+                    Severity::Error => "errors",
+                    Severity::Warning => "warnings",
+                    Severity::Info => "info",
+                };
+                format!("{count} {name}")
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !self.suppressed.is_empty() {
+            write!(summary, " ({} suppressed)", self.suppressed.len()).unwrap();
+        }
+        summary
+    }
+
+    /// Summary of the findings grouped by lint, for pasting into mod.io comments or Discord.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "## Lint report\n").unwrap();
+        writeln!(out, "{}", self.summary()).unwrap();
+
+        for lint in self.lints.iter().filter_map(|id| LintDefinition::get(*id)) {
+            let findings = self.findings_for(lint.id).collect::<Vec<_>>();
+            if findings.is_empty() {
+                continue;
+            }
+            writeln!(out, "\n### {} ({})\n", lint.title, findings.len()).unwrap();
+            for finding in findings {
+                writeln!(
+                    out,
+                    "- **{}** {}",
+                    finding.severity.as_str(),
+                    finding.message
+                )
+                .unwrap();
+                if finding.mods.len() > 1 {
+                    for mod_spec in &finding.mods {
+                        writeln!(out, "  - {}", mod_spec.url).unwrap();
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Self-contained HTML page with the findings grouped by mod. Findings about several mods
+    /// are listed under each of them.
+    pub fn to_html(&self) -> String {
+        let mut mods = BTreeMap::<&ModSpecification, Vec<&LintFinding>>::new();
+        for mod_spec in self.mod_paths.keys() {
+            mods.entry(mod_spec).or_default();
+        }
+        for finding in &self.findings {
+            for mod_spec in &finding.mods {
+                mods.entry(mod_spec).or_default().push(finding);
+            }
+        }
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Lint report</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>"
+        )
+        .unwrap();
+        writeln!(out, "<h1>Lint report</h1>\n<p>{}</p>", self.summary()).unwrap();
+
+        let lints = self
+            .lints
+            .iter()
+            .map(|id| LintDefinition::get(*id).map_or(id.name(), |lint| lint.title))
+            .collect::<Vec<_>>();
+        writeln!(out, "<p class=\"lint\">Lints run: {}</p>", lints.join(", ")).unwrap();
+
+        for (mod_spec, findings) in mods {
+            let url = escape_html(&mod_spec.url);
+            if mod_spec.url.starts_with("http://") || mod_spec.url.starts_with("https://") {
+                writeln!(out, "<h2><a href=\"{url}\">{url}</a></h2>").unwrap();
+            } else {
+                writeln!(out, "<h2>{url}</h2>").unwrap();
+            }
+            if findings.is_empty() {
+                writeln!(out, "<p>No findings.</p>").unwrap();
+                continue;
+            }
+            writeln!(out, "<ul>").unwrap();
+            for finding in findings {
+                let severity = finding.severity.as_str();
+                writeln!(
+                    out,
+                    "<li><span class=\"{severity}\">{severity}</span> {} <span class=\"lint\">({})</span></li>",
+                    message_html(&finding.message),
+                    escape_html(lint_title(finding))
+                )
+                .unwrap();
+            }
+            writeln!(out, "</ul>").unwrap();
+        }
+
+        writeln!(out, "</body>\n</html>").unwrap();
+        out
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use fs_err as fs;
+use tracing::warn;
+
+use crate::get_paks_from_data;
+use crate::providers::{ModSpecification, ModStore, ReadSeek};
+
+use super::{resolve_path, LintDefinition, LintReport};
+
+/// A mod whose pak was rewritten without the files reported by fixable lints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedMod {
+    pub mod_spec: ModSpecification,
+    /// Path of the linted file of the mod.
+    pub source: PathBuf,
+    /// Path of the cleaned copy in the blob cache, a pak or an archive of paks like the mod.
+    pub path: PathBuf,
+    /// Lowercase paths of the removed files relative to the game root.
+    pub removed: BTreeSet<String>,
+}
+
+/// Size of the NUL padded compression method names at the end of the pak footer.
+const COMPRESSION_NAME_SIZE: usize = 32;
+
+/// Reads the compression methods listed in the pak footer, in the order they are used when
+/// writing.
+fn read_compression(
+    pak: &mut Box<dyn ReadSeek>,
+    version: repak::Version,
+) -> Result<Vec<repak::Compression>> {
+    let count = match version {
+        // older paks don't list the methods, they only ever used Zlib
+        v if v < repak::Version::V8A => return Ok(vec![repak::Compression::Zlib]),
+        repak::Version::V8A => 4,
+        _ => 5,
+    };
+    let mut names = vec![0; count * COMPRESSION_NAME_SIZE];
+    pak.seek(SeekFrom::End(-(names.len() as i64)))?;
+    pak.read_exact(&mut names)?;
+
+    names
+        .chunks(COMPRESSION_NAME_SIZE)
+        .map(|name| {
+            String::from_utf8_lossy(name)
+                .trim_end_matches('\0')
+                .to_string()
+        })
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            Ok(match name.to_ascii_lowercase().as_str() {
+                "zlib" => repak::Compression::Zlib,
+                "gzip" => repak::Compression::Gzip,
+                #[cfg(feature = "oodle")]
+                "oodle" => repak::Compression::Oodle,
+                _ => bail!("pak uses compression method `{name}` which can't be written"),
+            })
+        })
+        .collect()
+}
+
+/// Writes a copy of `pak` without the entries whose lowercase path relative to the game root is
+/// in `remove`, keeping its version, mount point and compression. Returns the new pak along with
+/// the paths that were removed.
+fn fix_pak(
+    mut pak: Box<dyn ReadSeek>,
+    remove: &BTreeSet<String>,
+) -> Result<(Vec<u8>, BTreeSet<String>)> {
+    let pak_reader = repak::PakBuilder::new().reader(&mut pak)?;
+    let compression = read_compression(&mut pak, pak_reader.version())?;
+
+    let mut removed = BTreeSet::new();
+    let mut out = Cursor::new(vec![]);
+    let mut pak_writer = repak::PakBuilder::new().compression(compression).writer(
+        &mut out,
+        pak_reader.version(),
+        pak_reader.mount_point().to_string(),
+        None,
+    );
+    for p in pak_reader.files() {
+        if let Ok(path) = resolve_path(pak_reader.mount_point(), &p)
+            && remove.contains(&path.to_ascii_lowercase())
+        {
+            removed.insert(path.to_ascii_lowercase());
+            continue;
+        }
+        let data = pak_reader.get(&p, &mut pak)?;
+        pak_writer.write_file(&p, &data)?;
+    }
+    pak_writer.write_index()?;
+
+    Ok((out.into_inner(), removed))
+}
+
+/// Writes a copy of the mod at `path` without the entries in `remove`, see [`fix_pak`]. The paks of
+/// archives are written to a new archive under the same names, so disabled paks stay disabled.
+fn fix_mod(path: &Path, remove: &BTreeSet<String>) -> Result<(Vec<u8>, BTreeSet<String>)> {
+    let paks = get_paks_from_data(Box::new(BufReader::new(fs::File::open(path)?)), |_| true)?;
+
+    let mut removed = BTreeSet::new();
+    let mut cleaned = vec![];
+    for (name, pak) in paks {
+        let (data, pak_removed) = fix_pak(pak, remove)
+            .with_context(|| format!("failed to clean {}", name.as_deref().unwrap_or("pak")))?;
+        removed.extend(pak_removed);
+        cleaned.push((name, data));
+    }
+
+    // a bare pak rather than an archive
+    if let [(None, _)] = cleaned.as_slice() {
+        return Ok((cleaned.remove(0).1, removed));
+    }
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(vec![]));
+    // paks are compressed already
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (name, data) in cleaned {
+        zip.start_file(name.context("pak without name in archive")?, options)?;
+        zip.write_all(&data)?;
+    }
+    Ok((zip.finish()?.into_inner(), removed))
+}
+
+/// Writes copies of the mods in `report` without the files reported by fixable lints to the blob
+/// cache of `store`. Only mods that had files removed are returned. Files next to the paks in mod
+/// archives aren't kept. Mods that can't be cleaned are skipped with a warning.
+pub fn fix_mods(report: &LintReport, store: &ModStore) -> Result<Vec<FixedMod>> {
+    let mut remove = BTreeMap::<&ModSpecification, BTreeSet<String>>::new();
+    for finding in &report.findings {
+        if !LintDefinition::get(finding.lint).is_some_and(|l| l.fixable) {
+            continue;
+        }
+        let Some(path) = &finding.path else {
+            continue;
+        };
+        for mod_spec in &finding.mods {
+            remove.entry(mod_spec).or_default().insert(path.clone());
+        }
+    }
+
+    let mut fixed_mods = vec![];
+    for (mod_spec, remove) in remove {
+        let Some(mod_path) = report.mod_paths.get(mod_spec) else {
+            bail!("{} was not linted", mod_spec.url);
+        };
+        let (data, removed) = match fix_mod(mod_path, &remove) {
+            Ok(fixed) => fixed,
+            Err(e) => {
+                warn!("failed to write cleaned copy of {}: {e:#}", mod_spec.url);
+                continue;
+            }
+        };
+        if removed.is_empty() {
+            continue;
+        }
+        fixed_mods.push(FixedMod {
+            mod_spec: mod_spec.clone(),
+            source: mod_path.clone(),
+            path: store.store_blob(&data)?,
+            removed,
+        });
+    }
+
+    Ok(fixed_mods)
+}
@@ -0,0 +1,75 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{bail, Result};
+use mint_lib::GameVersion;
+
+use crate::providers::ModSpecification;
+
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const GAME_VERSION: Self = LintId::new("game_version");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::GAME_VERSION,
+        title: "Mods not made for the installed game version",
+        description: "Mods whose mod.io version tags don't include the version of the installed game. Mods without version tags aren't checked.",
+        severity: Severity::Warning,
+        requires_game_pak: true,
+        fixable: false,
+        check: findings,
+    }
+}
+
+#[derive(Default)]
+pub struct GameVersionLint;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameVersionMismatches {
+    pub game_version: GameVersion,
+    /// Mods along with the game versions they declare support for.
+    pub mods: BTreeMap<ModSpecification, BTreeSet<String>>,
+}
+
+impl Lint for GameVersionLint {
+    type Output = GameVersionMismatches;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output> {
+        let Some(game_pak_path) = &lcx.fsd_pak_path else {
+            bail!("GameVersionLint requires specifying a valid game pak path");
+        };
+        let game_version = GameVersion::from_pak_path(game_pak_path)?;
+
+        let mods = lcx
+            .mods
+            .iter()
+            .filter_map(|(mod_spec, _)| {
+                let versions = &lcx.mod_infos.get(mod_spec)?.modio_tags.as_ref()?.versions;
+                (!versions.is_empty() && !versions.iter().any(|v| game_version.matches_tag(v)))
+                    .then(|| (mod_spec.clone(), versions.clone()))
+            })
+            .collect();
+
+        Ok(GameVersionMismatches { game_version, mods })
+    }
+}
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    let mismatches = GameVersionLint.check_mods(lcx)?;
+    let game_version = &mismatches.game_version;
+
+    Ok(mismatches
+        .mods
+        .into_iter()
+        .map(|(mod_spec, versions)| {
+            let versions = versions.into_iter().collect::<Vec<_>>().join(", ");
+            let message = format!(
+                "{} supports game versions {versions} but {game_version} is installed",
+                mod_spec.url
+            );
+            LintFinding::new(LintId::GAME_VERSION, mod_spec, None, message)
+        })
+        .collect())
+}
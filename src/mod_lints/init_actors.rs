@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+use anyhow::Result;
+use unreal_asset::{engine_version::EngineVersion, exports::ExportBaseTrait, Asset};
+
+use crate::providers::ModSpecification;
+
+use super::{resolve_path, Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const INIT_ACTORS: Self = LintId::new("init_actors");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::INIT_ACTORS,
+        title: "Mods with broken init actors",
+        description: "Blueprints meant to be spawned as init actors that won't be: names that don't exactly match `InitSpaceRig` or `InitCave`, init actors outside `FSD/Content`, the same init actor in several mods and blueprints without a generated class.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
+
+/// File stems registered as init actors when integrating, compared case-insensitively.
+const INIT_ACTOR_NAMES: [&str; 2] = ["InitSpaceRig", "InitCave"];
+
+#[derive(Default)]
+pub struct InitActorsLint;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InitActorProblem {
+    /// The name resembles an init actor name without matching it, carrying the expected name.
+    NearMiss(&'static str),
+    /// Init actors are only registered relative to `FSD/Content`.
+    OutsideContent,
+    /// The blueprint has no `<name>_C` export to spawn.
+    MissingGeneratedClass,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InitActors {
+    /// Problems by mod and path of the blueprint relative to the game root.
+    pub problems: BTreeMap<ModSpecification, BTreeMap<String, InitActorProblem>>,
+    /// Lowercase paths of init actors provided by more than one mod, along with the mods in load
+    /// order. Only the first one is spawned.
+    pub duplicates: BTreeMap<String, Vec<ModSpecification>>,
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.bytes().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = (prev + usize::from(ca != *cb)).min(row[j] + 1).min(cur + 1);
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+/// Returns the init actor name `stem` was probably meant to be, if it isn't one already.
+fn near_miss(stem: &str) -> Option<&'static str> {
+    if INIT_ACTOR_NAMES
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(stem))
+    {
+        return None;
+    }
+    let normalized = normalize(stem);
+    INIT_ACTOR_NAMES.into_iter().find(|name| {
+        let target = name.to_ascii_lowercase();
+        normalized.contains(&target) || edit_distance(&normalized, &target) <= 2
+    })
+}
+
+/// Whether the blueprint contains an export for its generated class, or `None` if it can't be
+/// parsed.
+fn has_generated_class(stem: &str, uasset: &[u8], uexp: &[u8]) -> Option<bool> {
+    // malformed assets can make the parser panic, they are reported by the corrupt assets lint
+    let asset = std::panic::catch_unwind(|| {
+        Asset::new(
+            Cursor::new(uasset),
+            Some(Cursor::new(uexp)),
+            EngineVersion::VER_UE4_27,
+            None,
+        )
+        .ok()
+    })
+    .ok()
+    .flatten()?;
+    let class_name = format!("{stem}_C");
+    Some(asset.asset_data.exports.iter().any(|export| {
+        export
+            .get_base_export()
+            .object_name
+            .get_content(|n| n.eq_ignore_ascii_case(&class_name))
+    }))
+}
+
+impl Lint for InitActorsLint {
+    type Output = InitActors;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output> {
+        let mut init_actors = InitActors::default();
+        let mut providers = BTreeMap::<String, Vec<ModSpecification>>::new();
+
+        lcx.for_each_mod(
+            |mod_spec, pak_read_seek, pak_reader| {
+                let mut problems = BTreeMap::new();
+                for p in pak_reader.files() {
+                    let Ok(path) = resolve_path(pak_reader.mount_point(), &p) else {
+                        continue;
+                    };
+                    let Some(stem) = path
+                        .rsplit('/')
+                        .next()
+                        .and_then(|name| name.strip_suffix(".uasset"))
+                    else {
+                        continue;
+                    };
+
+                    if let Some(expected) = near_miss(stem) {
+                        problems.insert(path.clone(), InitActorProblem::NearMiss(expected));
+                        continue;
+                    }
+                    if !INIT_ACTOR_NAMES
+                        .iter()
+                        .any(|name| name.eq_ignore_ascii_case(stem))
+                    {
+                        continue;
+                    }
+
+                    // integration builds the class path from the exact prefix
+                    if !path.starts_with("FSD/Content/") {
+                        problems.insert(path.clone(), InitActorProblem::OutsideContent);
+                        continue;
+                    }
+
+                    let uexp_path = format!("{}.uexp", p.strip_suffix(".uasset").unwrap());
+                    if let Ok(uexp) = pak_reader.get(&uexp_path, pak_read_seek) {
+                        let uasset = pak_reader.get(&p, pak_read_seek)?;
+                        if has_generated_class(stem, &uasset, &uexp) == Some(false) {
+                            problems.insert(path.clone(), InitActorProblem::MissingGeneratedClass);
+                        }
+                    }
+
+                    let mods = providers.entry(path.to_ascii_lowercase()).or_default();
+                    if !mods.contains(&mod_spec) {
+                        mods.push(mod_spec.clone());
+                    }
+                }
+                if !problems.is_empty() {
+                    init_actors
+                        .problems
+                        .entry(mod_spec)
+                        .or_default()
+                        .extend(problems);
+                }
+                Ok(())
+            },
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+        )?;
+
+        init_actors.duplicates = providers
+            .into_iter()
+            .filter(|(_, mods)| mods.len() > 1)
+            .collect();
+
+        Ok(init_actors)
+    }
+}
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    let init_actors = InitActorsLint.check_mods(lcx)?;
+
+    let problems = init_actors
+        .problems
+        .into_iter()
+        .flat_map(|(mod_spec, problems)| {
+            problems.into_iter().map(move |(path, problem)| {
+                let url = &mod_spec.url;
+                let (severity, message) = match problem {
+                    InitActorProblem::NearMiss(expected) => (
+                        Severity::Warning,
+                        format!(
+                            "`{path}` in {url} looks like an init actor but only blueprints named exactly `{expected}` are spawned"
+                        ),
+                    ),
+                    InitActorProblem::OutsideContent => (
+                        Severity::Error,
+                        format!(
+                            "init actor `{path}` in {url} is outside `FSD/Content` and can't be registered"
+                        ),
+                    ),
+                    InitActorProblem::MissingGeneratedClass => (
+                        Severity::Error,
+                        format!(
+                            "init actor `{path}` in {url} has no generated class and can't be spawned"
+                        ),
+                    ),
+                };
+                LintFinding {
+                    severity,
+                    ..LintFinding::new(
+                        LintId::INIT_ACTORS,
+                        mod_spec.clone(),
+                        Some(path.to_ascii_lowercase()),
+                        message,
+                    )
+                }
+            })
+        });
+
+    let duplicates = init_actors.duplicates.into_iter().map(|(path, mods)| {
+        let first = mods[0].clone();
+        let message = format!(
+            "init actor `{path}` is provided by several mods, only the one from {} is spawned",
+            first.url
+        );
+        LintFinding {
+            mods,
+            ..LintFinding::new(LintId::INIT_ACTORS, first, Some(path), message)
+        }
+    });
+
+    Ok(problems.chain(duplicates).collect())
+}
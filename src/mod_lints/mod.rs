@@ -1,42 +1,59 @@
 mod archive_multiple_paks;
 mod archive_only_non_pak_files;
 mod asset_register_bin;
+mod bad_paths;
 mod conflicting_mods;
+mod corrupt_assets;
+mod dangerous_files;
+mod duplicate_content;
 mod empty_archive;
+mod export;
+mod fix;
+mod game_version;
+mod init_actors;
 mod non_asset_files;
 mod outdated_pak_version;
+mod patched_assets;
 mod shader_files;
 mod split_asset_pairs;
+mod suppressions;
 mod unmodified_game_assets;
+mod unresolved_references;
 
-use std::collections::{BTreeMap, BTreeSet};
-use std::io::BufReader;
-use std::path::PathBuf;
+use std::collections::{BTreeSet, HashMap};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use fs_err as fs;
 use indexmap::IndexSet;
 use repak::PakReader;
 use tracing::trace;
 
-use crate::mod_lints::conflicting_mods::ConflictingModsLint;
-use crate::providers::{ModSpecification, ReadSeek};
+use crate::game_pak_index::GamePakIndex;
+use crate::providers::{ModInfo, ModSpecification, ReadSeek};
 use crate::{lint_get_all_files_from_data, GetAllFilesFromDataError, PakOrNotPak};
 
-use self::archive_multiple_paks::ArchiveMultiplePaksLint;
-use self::archive_only_non_pak_files::ArchiveOnlyNonPakFilesLint;
-use self::asset_register_bin::AssetRegisterBinLint;
-use self::empty_archive::EmptyArchiveLint;
-use self::non_asset_files::NonAssetFilesLint;
-use self::outdated_pak_version::OutdatedPakVersionLint;
-use self::shader_files::ShaderFilesLint;
+pub use self::fix::{fix_mods, FixedMod};
 pub use self::split_asset_pairs::SplitAssetPair;
-use self::split_asset_pairs::SplitAssetPairsLint;
-use self::unmodified_game_assets::UnmodifiedGameAssetsLint;
+pub use self::suppressions::{LintFindingKey, LintSuppression};
+pub use self::unresolved_references::{UnresolvedReference, UnresolvedReferencesLint};
 
 pub struct LintCtxt {
+    /// Mods to lint in load order, highest priority first like when integrating.
     pub(crate) mods: IndexSet<(ModSpecification, PathBuf)>,
+    /// Mods that aren't linted but may be suggested to fix findings, such as disabled mods of
+    /// the profile.
+    pub(crate) candidate_mods: IndexSet<(ModSpecification, PathBuf)>,
+    /// Paks inside the archives of mods that are skipped like when integrating.
+    pub(crate) disabled_paks: HashMap<ModSpecification, BTreeSet<String>>,
+    /// Metadata of the linted mods known to their providers, such as mod.io tags.
+    pub(crate) mod_infos: HashMap<ModSpecification, ModInfo>,
     pub(crate) fsd_pak_path: Option<PathBuf>,
+    /// Findings matching these aren't reported.
+    pub(crate) suppressions: BTreeSet<LintSuppression>,
+    /// Directory the game pak hash index is kept in. Without it the index is built on every run.
+    pub(crate) cache_dir: Option<PathBuf>,
 }
 
 impl LintCtxt {
@@ -45,11 +62,100 @@ impl LintCtxt {
         fsd_pak_path: Option<PathBuf>,
     ) -> Result<Self> {
         trace!("LintCtxt::init");
-        Ok(Self { mods, fsd_pak_path })
+        Ok(Self {
+            mods,
+            candidate_mods: IndexSet::new(),
+            disabled_paks: HashMap::new(),
+            mod_infos: HashMap::new(),
+            fsd_pak_path,
+            suppressions: BTreeSet::new(),
+            cache_dir: None,
+        })
+    }
+
+    pub fn with_candidate_mods(
+        mut self,
+        candidate_mods: IndexSet<(ModSpecification, PathBuf)>,
+    ) -> Self {
+        self.candidate_mods = candidate_mods;
+        self
+    }
+
+    pub fn with_disabled_paks(
+        mut self,
+        disabled_paks: HashMap<ModSpecification, BTreeSet<String>>,
+    ) -> Self {
+        self.disabled_paks = disabled_paks;
+        self
+    }
+
+    pub fn with_mod_infos(mut self, mod_infos: HashMap<ModSpecification, ModInfo>) -> Self {
+        self.mod_infos = mod_infos;
+        self
+    }
+
+    pub fn with_suppressions(mut self, suppressions: BTreeSet<LintSuppression>) -> Self {
+        self.suppressions = suppressions;
+        self
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: PathBuf) -> Self {
+        self.cache_dir = Some(cache_dir);
+        self
+    }
+
+    /// Hashes of the files of the game pak, loaded from the cache directory if possible.
+    pub fn game_pak_index(&self) -> Result<GamePakIndex> {
+        let Some(game_pak_path) = &self.fsd_pak_path else {
+            bail!("the game pak hash index requires specifying a valid game pak path");
+        };
+        match &self.cache_dir {
+            Some(cache_dir) => GamePakIndex::load_or_build(game_pak_path, cache_dir),
+            None => GamePakIndex::build(game_pak_path),
+        }
     }
 
     pub fn for_each_mod<F, EmptyArchiveHandler, OnlyNonPakFilesHandler, MultiplePakFilesHandler>(
         &self,
+        f: F,
+        empty_archive_handler: Option<EmptyArchiveHandler>,
+        only_non_pak_files_handler: Option<OnlyNonPakFilesHandler>,
+        multiple_pak_files_handler: Option<MultiplePakFilesHandler>,
+    ) -> Result<()>
+    where
+        F: FnMut(ModSpecification, &mut Box<dyn ReadSeek>, &PakReader) -> Result<()>,
+        EmptyArchiveHandler: FnMut(ModSpecification),
+        OnlyNonPakFilesHandler: FnMut(ModSpecification),
+        MultiplePakFilesHandler: FnMut(ModSpecification),
+    {
+        Self::visit_mods(
+            &self.mods,
+            &self.disabled_paks,
+            f,
+            empty_archive_handler,
+            only_non_pak_files_handler,
+            multiple_pak_files_handler,
+        )
+    }
+
+    /// Like [`LintCtxt::for_each_mod`] for the candidate mods.
+    pub fn for_each_candidate_mod<F>(&self, f: F) -> Result<()>
+    where
+        F: FnMut(ModSpecification, &mut Box<dyn ReadSeek>, &PakReader) -> Result<()>,
+    {
+        Self::visit_mods(
+            &self.candidate_mods,
+            &self.disabled_paks,
+            f,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+        )
+    }
+
+    fn visit_mods<F, EmptyArchiveHandler, OnlyNonPakFilesHandler, MultiplePakFilesHandler>(
+        mods: &IndexSet<(ModSpecification, PathBuf)>,
+        disabled_paks: &HashMap<ModSpecification, BTreeSet<String>>,
         mut f: F,
         mut empty_archive_handler: Option<EmptyArchiveHandler>,
         mut only_non_pak_files_handler: Option<OnlyNonPakFilesHandler>,
@@ -61,7 +167,7 @@ impl LintCtxt {
         OnlyNonPakFilesHandler: FnMut(ModSpecification),
         MultiplePakFilesHandler: FnMut(ModSpecification),
     {
-        for (mod_spec, mod_pak_path) in &self.mods {
+        for (mod_spec, mod_pak_path) in mods {
             let maybe_archive_reader = Box::new(BufReader::new(fs::File::open(mod_pak_path)?));
             let bufs = match lint_get_all_files_from_data(maybe_archive_reader) {
                 Ok(bufs) => bufs,
@@ -72,7 +178,7 @@ impl LintCtxt {
                         }
                         continue;
                     }
-                    GetAllFilesFromDataError::OnlyNonPakFiles => {
+                    GetAllFilesFromDataError::OnlyNonPakFiles(_) => {
                         if let Some(ref mut handler) = only_non_pak_files_handler {
                             handler(mod_spec.clone());
                         }
@@ -84,8 +190,11 @@ impl LintCtxt {
 
             let mut individual_pak_readers = bufs
                 .into_iter()
-                .filter_map(|(_, pak_or_non_pak)| match pak_or_non_pak {
-                    PakOrNotPak::Pak(individual_pak_reader) => Some(individual_pak_reader),
+                .filter_map(|(path, pak_or_non_pak)| match pak_or_non_pak {
+                    PakOrNotPak::Pak(individual_pak_reader) => Some((
+                        path.to_string_lossy().replace('\\', "/"),
+                        individual_pak_reader,
+                    )),
                     PakOrNotPak::NotPak(_) => None,
                 })
                 .collect::<Vec<_>>();
@@ -96,9 +205,41 @@ impl LintCtxt {
                 }
             }
 
-            let mut first_pak_read_seek = individual_pak_readers.remove(0);
-            let pak_reader = repak::PakBuilder::new().reader(&mut first_pak_read_seek)?;
-            f(mod_spec.clone(), &mut first_pak_read_seek, &pak_reader)?
+            // integration uses every pak not disabled in the mod's config, ordered by their path
+            // inside the archive
+            if let Some(disabled_paks) = disabled_paks.get(mod_spec) {
+                individual_pak_readers.retain(|(path, _)| !disabled_paks.contains(path));
+            }
+            individual_pak_readers.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (_, mut pak_read_seek) in individual_pak_readers {
+                let pak_reader = repak::PakBuilder::new().reader(&mut pak_read_seek)?;
+                f(mod_spec.clone(), &mut pak_read_seek, &pak_reader)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calls `f` with the contents of every file of the mod archives that isn't a pak, such as
+    /// readmes shipped next to the pak in a zip.
+    pub fn for_each_archive_file<F>(&self, mut f: F) -> Result<()>
+    where
+        F: FnMut(ModSpecification, &Path, Vec<u8>) -> Result<()>,
+    {
+        for (mod_spec, mod_pak_path) in &self.mods {
+            let maybe_archive_reader = Box::new(BufReader::new(fs::File::open(mod_pak_path)?));
+            let files = match lint_get_all_files_from_data(maybe_archive_reader) {
+                Ok(files) | Err(GetAllFilesFromDataError::OnlyNonPakFiles(files)) => files,
+                Err(GetAllFilesFromDataError::EmptyArchive) => continue,
+                Err(GetAllFilesFromDataError::Other(e)) => return Err(e),
+            };
+            for (path, pak_or_non_pak) in files {
+                if let PakOrNotPak::NotPak(mut read_seek) = pak_or_non_pak {
+                    let mut data = vec![];
+                    read_seek.read_to_end(&mut data)?;
+                    f(mod_spec.clone(), &path, data)?;
+                }
+            }
         }
 
         Ok(())
@@ -116,19 +257,17 @@ impl LintCtxt {
     {
         self.for_each_mod(
             |mod_spec, pak_read_seek, pak_reader| {
-                let mount = PathBuf::from(pak_reader.mount_point());
                 for p in pak_reader.files() {
-                    let path = mount.join(&p);
-                    let path_buf = path
-                        .strip_prefix("../../../")
-                        .context("prefix does not match")?;
-                    let normalized_path = &path_buf.to_string_lossy().replace('\\', "/");
-                    let normalized_path = normalized_path.to_ascii_lowercase();
+                    // reported by the bad_paths lint
+                    let Ok(path) = resolve_path(pak_reader.mount_point(), &p) else {
+                        continue;
+                    };
+                    let normalized_path = path.to_ascii_lowercase();
                     f(
                         mod_spec.clone(),
                         pak_read_seek,
                         pak_reader,
-                        path_buf.to_path_buf(),
+                        PathBuf::from(path),
                         normalized_path,
                     )?
                 }
@@ -142,67 +281,176 @@ impl LintCtxt {
     }
 }
 
+/// Mount point of paks whose entries are relative to the game root.
+pub(crate) const STANDARD_MOUNT_POINT: &str = "../../../";
+
+/// Why a pak entry can't be placed inside the game directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UnresolvablePath {
+    Absolute,
+    /// `..` components lead out of the game directory.
+    OutsideGameRoot,
+}
+
+/// Path of the pak entry `path` relative to the game root with `/` as separator, as the game
+/// resolves it from the `FSD/Content/Paks` directory.
+pub(crate) fn resolve_path(mount: &str, path: &str) -> Result<String, UnresolvablePath> {
+    let full = format!("{mount}/{path}");
+    if full.starts_with(['/', '\\']) || full.as_bytes().get(1) == Some(&b':') {
+        return Err(UnresolvablePath::Absolute);
+    }
+    let mut components = vec!["FSD", "Content", "Paks"];
+    for c in full.split(['/', '\\']) {
+        match c {
+            "" | "." => {}
+            ".." => {
+                components.pop().ok_or(UnresolvablePath::OutsideGameRoot)?;
+            }
+            c => components.push(c),
+        }
+    }
+    Ok(components.join("/"))
+}
+
 pub trait Lint {
     type Output;
 
     fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output>;
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LintId {
     name: &'static str,
 }
 
 impl LintId {
+    pub const fn new(name: &'static str) -> Self {
+        LintId { name }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
     pub fn to_name_lower(&self) -> String {
         self.name.to_ascii_lowercase()
     }
 
-    pub const CONFLICTING: Self = LintId {
-        name: "conflicting",
-    };
-    pub const ASSET_REGISTRY_BIN: Self = LintId {
-        name: "asset_registry_bin",
-    };
-    pub const SHADER_FILES: Self = LintId {
-        name: "shader_files",
-    };
-    pub const OUTDATED_PAK_VERSION: Self = LintId {
-        name: "outdated_pak_version",
-    };
-    pub const EMPTY_ARCHIVE: Self = LintId {
-        name: "empty_archive",
-    };
-    pub const ARCHIVE_WITH_ONLY_NON_PAK_FILES: Self = LintId {
-        name: "archive_only_non_pak_files",
-    };
-    pub const ARCHIVE_WITH_MULTIPLE_PAKS: Self = LintId {
-        name: "archive_with_multiple_paks",
-    };
-    pub const NON_ASSET_FILES: Self = LintId {
-        name: "non_asset_files",
-    };
-    pub const SPLIT_ASSET_PAIRS: Self = LintId {
-        name: "split_asset_pairs",
-    };
-    pub const UNMODIFIED_GAME_ASSETS: Self = LintId {
-        name: "unmodified_game_assets",
-    };
+    /// Looks up a registered lint by name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        inventory::iter::<LintDefinition>()
+            .map(|l| l.id)
+            .find(|id| id.name == name)
+    }
+}
+
+impl std::fmt::Display for LintId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A lint, registered with [`inventory::submit!`] next to its implementation.
+pub struct LintDefinition {
+    pub id: LintId,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub severity: Severity,
+    /// Whether the lint compares mods against the game pak, which must be passed to [`run_lints`].
+    pub requires_game_pak: bool,
+    /// Whether the files reported by the lint can be removed from mods with [`fix_mods`].
+    pub fixable: bool,
+    check: fn(&LintCtxt) -> Result<Vec<LintFinding>>,
+}
+
+impl std::fmt::Debug for LintDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LintDefinition")
+            .field("id", &self.id)
+            .field("severity", &self.severity)
+            .field("requires_game_pak", &self.requires_game_pak)
+            .field("fixable", &self.fixable)
+            .finish()
+    }
+}
+
+inventory::collect!(LintDefinition);
+
+impl LintDefinition {
+    /// All registered lints ordered by ID.
+    pub fn all() -> Vec<&'static LintDefinition> {
+        let mut lints = inventory::iter::<LintDefinition>().collect::<Vec<_>>();
+        lints.sort_by_key(|l| l.id);
+        lints
+    }
+
+    pub fn get(id: LintId) -> Option<&'static LintDefinition> {
+        inventory::iter::<LintDefinition>().find(|l| l.id == id)
+    }
+}
+
+/// A single problem found by a lint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    pub lint: LintId,
+    pub severity: Severity,
+    /// Mods the finding is about, usually one.
+    pub mods: Vec<ModSpecification>,
+    /// Normalized path of the file the finding is about, if any.
+    pub path: Option<String>,
+    pub message: String,
+}
+
+impl LintFinding {
+    /// A finding about a single mod with the default severity of `lint`.
+    pub(crate) fn new(
+        lint: LintId,
+        mod_spec: ModSpecification,
+        path: Option<String>,
+        message: String,
+    ) -> Self {
+        Self {
+            lint,
+            severity: LintDefinition::get(lint).map_or(Severity::Warning, |l| l.severity),
+            mods: vec![mod_spec],
+            path,
+            message,
+        }
+    }
 }
 
 #[derive(Default, Debug)]
 pub struct LintReport {
-    pub conflicting_mods: Option<BTreeMap<String, IndexSet<ModSpecification>>>,
-    pub asset_register_bin_mods: Option<BTreeMap<ModSpecification, BTreeSet<String>>>,
-    pub shader_file_mods: Option<BTreeMap<ModSpecification, BTreeSet<String>>>,
-    pub outdated_pak_version_mods: Option<BTreeMap<ModSpecification, repak::Version>>,
-    pub empty_archive_mods: Option<BTreeSet<ModSpecification>>,
-    pub archive_with_only_non_pak_files_mods: Option<BTreeSet<ModSpecification>>,
-    pub archive_with_multiple_paks_mods: Option<BTreeSet<ModSpecification>>,
-    pub non_asset_file_mods: Option<BTreeMap<ModSpecification, BTreeSet<String>>>,
-    pub split_asset_pairs_mods:
-        Option<BTreeMap<ModSpecification, BTreeMap<String, SplitAssetPair>>>,
-    pub unmodified_game_assets_mods: Option<BTreeMap<ModSpecification, BTreeSet<String>>>,
+    /// Lints that were run, in the order they were run.
+    pub lints: Vec<LintId>,
+    pub findings: Vec<LintFinding>,
+    /// Findings that matched a suppression and aren't reported.
+    pub suppressed: Vec<LintFinding>,
+    /// Paths of the linted mods.
+    pub mod_paths: HashMap<ModSpecification, PathBuf>,
+}
+
+impl LintReport {
+    pub fn findings_for(&self, lint: LintId) -> impl Iterator<Item = &LintFinding> {
+        self.findings.iter().filter(move |f| f.lint == lint)
+    }
 }
 
 pub fn run_lints(
@@ -210,53 +458,33 @@ pub fn run_lints(
     mods: IndexSet<(ModSpecification, PathBuf)>,
     fsd_pak_path: Option<PathBuf>,
 ) -> Result<LintReport> {
-    let lint_ctxt = LintCtxt::init(mods, fsd_pak_path)?;
-    let mut lint_report = LintReport::default();
+    run_lints_with_ctxt(enabled_lints, &LintCtxt::init(mods, fsd_pak_path)?)
+}
+
+pub fn run_lints_with_ctxt(
+    enabled_lints: &BTreeSet<LintId>,
+    lint_ctxt: &LintCtxt,
+) -> Result<LintReport> {
+    let mut lint_report = LintReport {
+        mod_paths: lint_ctxt.mods.iter().cloned().collect(),
+        ..Default::default()
+    };
 
     for lint_id in enabled_lints {
-        match *lint_id {
-            LintId::CONFLICTING => {
-                let res = ConflictingModsLint.check_mods(&lint_ctxt)?;
-                lint_report.conflicting_mods = Some(res);
-            }
-            LintId::ASSET_REGISTRY_BIN => {
-                let res = AssetRegisterBinLint.check_mods(&lint_ctxt)?;
-                lint_report.asset_register_bin_mods = Some(res);
-            }
-            LintId::SHADER_FILES => {
-                let res = ShaderFilesLint.check_mods(&lint_ctxt)?;
-                lint_report.shader_file_mods = Some(res);
-            }
-            LintId::OUTDATED_PAK_VERSION => {
-                let res = OutdatedPakVersionLint.check_mods(&lint_ctxt)?;
-                lint_report.outdated_pak_version_mods = Some(res);
-            }
-            LintId::EMPTY_ARCHIVE => {
-                let res = EmptyArchiveLint.check_mods(&lint_ctxt)?;
-                lint_report.empty_archive_mods = Some(res);
-            }
-            LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES => {
-                let res = ArchiveOnlyNonPakFilesLint.check_mods(&lint_ctxt)?;
-                lint_report.archive_with_only_non_pak_files_mods = Some(res);
-            }
-            LintId::ARCHIVE_WITH_MULTIPLE_PAKS => {
-                let res = ArchiveMultiplePaksLint.check_mods(&lint_ctxt)?;
-                lint_report.archive_with_multiple_paks_mods = Some(res);
-            }
-            LintId::NON_ASSET_FILES => {
-                let res = NonAssetFilesLint.check_mods(&lint_ctxt)?;
-                lint_report.non_asset_file_mods = Some(res);
-            }
-            LintId::SPLIT_ASSET_PAIRS => {
-                let res = SplitAssetPairsLint.check_mods(&lint_ctxt)?;
-                lint_report.split_asset_pairs_mods = Some(res);
-            }
-            LintId::UNMODIFIED_GAME_ASSETS => {
-                let res = UnmodifiedGameAssetsLint.check_mods(&lint_ctxt)?;
-                lint_report.unmodified_game_assets_mods = Some(res);
-            }
-            _ => unimplemented!(),
+        let Some(lint) = LintDefinition::get(*lint_id) else {
+            bail!("unknown lint `{lint_id}`");
+        };
+        if lint.requires_game_pak && lint_ctxt.fsd_pak_path.is_none() {
+            bail!("lint `{lint_id}` requires specifying a valid game pak path");
         }
+        let findings =
+            (lint.check)(lint_ctxt).with_context(|| format!("lint `{lint_id}` failed"))?;
+        lint_report.lints.push(*lint_id);
+        lint_report.findings.extend(findings);
+    }
+
+    for suppression in &lint_ctxt.suppressions {
+        lint_report.suppress(suppression);
     }
 
     Ok(lint_report)
@@ -4,12 +4,28 @@ use anyhow::Result;
 
 use crate::providers::ModSpecification;
 
-use super::{Lint, LintCtxt};
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const NON_ASSET_FILES: Self = LintId::new("non_asset_files");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::NON_ASSET_FILES,
+        title: "Mods containing non-asset files",
+        description: "Files that are not Unreal assets, which the game ignores.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: true,
+        check: findings,
+    }
+}
 
 #[derive(Default)]
 pub struct NonAssetFilesLint;
 
-const ENDS_WITH_WHITE_LIST: [&str; 7] = [
+pub(super) const ENDS_WITH_WHITE_LIST: [&str; 7] = [
     ".uexp",
     ".uasset",
     ".ubulk",
@@ -43,3 +59,21 @@ impl Lint for NonAssetFilesLint {
         Ok(non_asset_files)
     }
 }
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(NonAssetFilesLint
+        .check_mods(lcx)?
+        .into_iter()
+        .flat_map(|(mod_spec, paths)| {
+            paths.into_iter().map(move |path| {
+                let message = format!("{} includes non-asset file `{path}`", mod_spec.url);
+                LintFinding::new(
+                    LintId::NON_ASSET_FILES,
+                    mod_spec.clone(),
+                    Some(path),
+                    message,
+                )
+            })
+        })
+        .collect())
+}
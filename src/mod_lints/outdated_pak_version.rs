@@ -2,10 +2,26 @@ use std::collections::BTreeMap;
 
 use crate::providers::ModSpecification;
 
-use super::{Lint, LintCtxt};
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
 
 use anyhow::Result;
 
+impl LintId {
+    pub const OUTDATED_PAK_VERSION: Self = LintId::new("outdated_pak_version");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::OUTDATED_PAK_VERSION,
+        title: "Mods containing outdated pak version",
+        description: "Paks older than the version used by the game.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
+
 #[derive(Default)]
 pub struct OutdatedPakVersionLint;
 
@@ -17,8 +33,17 @@ impl Lint for OutdatedPakVersionLint {
 
         lcx.for_each_mod(
             |mod_spec, _, pak_reader| {
-                if pak_reader.version() < repak::Version::V11 {
-                    outdated_pak_version_mods.insert(mod_spec.clone(), pak_reader.version());
+                let version = pak_reader.version();
+                if version < repak::Version::V11 {
+                    // the oldest pak of the mod
+                    outdated_pak_version_mods
+                        .entry(mod_spec.clone())
+                        .and_modify(|v| {
+                            if version < *v {
+                                *v = version;
+                            }
+                        })
+                        .or_insert(version);
                 }
                 Ok(())
             },
@@ -30,3 +55,14 @@ impl Lint for OutdatedPakVersionLint {
         Ok(outdated_pak_version_mods)
     }
 }
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(OutdatedPakVersionLint
+        .check_mods(lcx)?
+        .into_iter()
+        .map(|(mod_spec, version)| {
+            let message = format!("{} includes outdated pak version {version}", mod_spec.url);
+            LintFinding::new(LintId::OUTDATED_PAK_VERSION, mod_spec, None, message)
+        })
+        .collect())
+}
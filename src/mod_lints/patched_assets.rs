@@ -0,0 +1,150 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use strum::IntoEnumIterator;
+
+use crate::integrate::{integration_files, IntegrationPatch, INTEGRATION_DIR};
+use crate::providers::ModSpecification;
+
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const PATCHED_ASSETS: Self = LintId::new("patched_assets");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::PATCHED_ASSETS,
+        title: "Mods overriding assets patched by mint",
+        description: "Game assets mint patches when integrating and files of mint's own integration assets. Overriding them interferes with the changes mint makes.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
+
+#[derive(Default)]
+pub struct PatchedAssetsLint;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PatchedAssetsOverrides {
+    /// Lowercase paths of patched assets without extension, along with the patches modifying them
+    /// and the mods overriding them in load order.
+    pub patched: BTreeMap<String, (Vec<IntegrationPatch>, Vec<ModSpecification>)>,
+    /// Lowercase paths of files in mint's integration directory, along with whether mint ships a
+    /// file at that path and the mods containing them.
+    pub integration: BTreeMap<String, (bool, BTreeSet<ModSpecification>)>,
+}
+
+impl Lint for PatchedAssetsLint {
+    type Output = PatchedAssetsOverrides;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output> {
+        let mut patches = BTreeMap::<String, Vec<IntegrationPatch>>::new();
+        for patch in IntegrationPatch::iter() {
+            for path in patch.asset_paths() {
+                patches
+                    .entry(path.to_ascii_lowercase())
+                    .or_default()
+                    .push(patch);
+            }
+        }
+        let integration_dir = format!("{}/", INTEGRATION_DIR.to_ascii_lowercase());
+        let integration_files = integration_files()?;
+
+        let mut overrides = PatchedAssetsOverrides::default();
+        lcx.for_each_mod_file(|mod_spec, _, _, _, normalized_path| {
+            if normalized_path.starts_with(&integration_dir) {
+                let replaced = integration_files.contains(&normalized_path);
+                overrides
+                    .integration
+                    .entry(normalized_path)
+                    .or_insert_with(|| (replaced, BTreeSet::new()))
+                    .1
+                    .insert(mod_spec);
+                return Ok(());
+            }
+
+            let Some(asset_path) = normalized_path
+                .strip_suffix(".uasset")
+                .or_else(|| normalized_path.strip_suffix(".uexp"))
+            else {
+                return Ok(());
+            };
+            if let Some(patches) = patches.get(asset_path) {
+                let (_, mods) = overrides
+                    .patched
+                    .entry(asset_path.to_string())
+                    .or_insert_with(|| (patches.clone(), vec![]));
+                if !mods.contains(&mod_spec) {
+                    mods.push(mod_spec);
+                }
+            }
+            Ok(())
+        })?;
+
+        Ok(overrides)
+    }
+}
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    let overrides = PatchedAssetsLint.check_mods(lcx)?;
+
+    let patched = overrides
+        .patched
+        .into_iter()
+        .map(|(path, (patches, mods))| {
+            let patches = patches
+                .iter()
+                .map(|patch| format!("\"{}\"", patch.name()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            // mint keeps the version of the last mod read, which is the one with the lowest
+            // priority
+            let patched_mod = mods.last().unwrap().clone();
+            let mut message = format!(
+                "`{path}` is patched by mint ({patches}); the version from {} is patched instead of the game's, so its own changes and mint's can break each other",
+                patched_mod.url
+            );
+            if mods.len() > 1 {
+                let discarded = mods[..mods.len() - 1]
+                    .iter()
+                    .map(|m| m.url.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                message.push_str(&format!(" and the versions from {discarded} are discarded"));
+            }
+            LintFinding {
+                mods,
+                ..LintFinding::new(LintId::PATCHED_ASSETS, patched_mod, Some(path), message)
+            }
+        });
+
+    let integration = overrides.integration.into_iter().map(|(path, (replaced, mods))| {
+        // only files mint ships itself are replaced, others end up in game next to mint's assets
+        let (severity, message) = if replaced {
+            (
+                Severity::Error,
+                format!("`{path}` is part of mint's mod integration and is replaced by mint's version, so the mod's changes to it have no effect"),
+            )
+        } else {
+            (
+                Severity::Warning,
+                format!("`{path}` is in mint's mod integration directory, which is reserved for mint's own assets and may clash with them in future versions"),
+            )
+        };
+        LintFinding {
+            severity,
+            mods: mods.iter().cloned().collect(),
+            ..LintFinding::new(
+                LintId::PATCHED_ASSETS,
+                mods.first().unwrap().clone(),
+                Some(path),
+                message,
+            )
+        }
+    });
+
+    Ok(patched.chain(integration).collect())
+}
@@ -4,7 +4,23 @@ use anyhow::Result;
 
 use crate::providers::ModSpecification;
 
-use super::{Lint, LintCtxt};
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const SHADER_FILES: Self = LintId::new("shader_files");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::SHADER_FILES,
+        title: "Mods containing shader files",
+        description: "Shader bytecode, which is skipped when integrating and can crash the game.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: true,
+        check: findings,
+    }
+}
 
 #[derive(Default)]
 pub struct ShaderFilesLint;
@@ -30,3 +46,16 @@ impl Lint for ShaderFilesLint {
         Ok(shader_file_mods)
     }
 }
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(ShaderFilesLint
+        .check_mods(lcx)?
+        .into_iter()
+        .flat_map(|(mod_spec, paths)| {
+            paths.into_iter().map(move |path| {
+                let message = format!("{} includes shader file `{path}`", mod_spec.url);
+                LintFinding::new(LintId::SHADER_FILES, mod_spec.clone(), Some(path), message)
+            })
+        })
+        .collect())
+}
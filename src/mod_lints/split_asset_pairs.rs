@@ -4,7 +4,23 @@ use tracing::trace;
 
 use crate::providers::ModSpecification;
 
-use super::{Lint, LintCtxt};
+use super::{Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const SPLIT_ASSET_PAIRS: Self = LintId::new("split_asset_pairs");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::SPLIT_ASSET_PAIRS,
+        title: "Mods containing split {uexp, uasset} pairs",
+        description: "A `.uasset` without its `.uexp` or the other way around.",
+        severity: Severity::Warning,
+        requires_game_pak: false,
+        fixable: false,
+        check: findings,
+    }
+}
 
 #[derive(Default)]
 pub struct SplitAssetPairsLint;
@@ -98,3 +114,28 @@ impl Lint for SplitAssetPairsLint {
         Ok(split_asset_pairs_mods)
     }
 }
+
+fn findings(lcx: &LintCtxt) -> anyhow::Result<Vec<LintFinding>> {
+    Ok(SplitAssetPairsLint
+        .check_mods(lcx)?
+        .into_iter()
+        .flat_map(|(mod_spec, files)| {
+            files.into_iter().map(move |(path, kind)| {
+                let message = match kind {
+                    SplitAssetPair::MissingUasset => {
+                        format!("`{path}` in {} missing matching .uasset file", mod_spec.url)
+                    }
+                    SplitAssetPair::MissingUexp => {
+                        format!("`{path}` in {} missing matching .uexp file", mod_spec.url)
+                    }
+                };
+                LintFinding::new(
+                    LintId::SPLIT_ASSET_PAIRS,
+                    mod_spec.clone(),
+                    Some(path),
+                    message,
+                )
+            })
+        })
+        .collect())
+}
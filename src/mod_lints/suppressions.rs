@@ -0,0 +1,93 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::providers::ModSpecification;
+
+use super::{LintFinding, LintReport};
+
+/// An expected finding that isn't reported, such as a conflict between two mods that is known to
+/// be harmless. Stored per profile.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LintSuppression {
+    /// Name of the lint whose findings are suppressed.
+    pub lint: String,
+    /// Mods the findings are about, which must be exactly those of a finding.
+    pub mods: BTreeSet<ModSpecification>,
+    /// Path the findings are about, `None` for findings without a path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Suppress findings about any path instead of only those about `path`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub all_paths: bool,
+}
+
+impl LintSuppression {
+    /// Suppresses findings like `finding`, about the same mods and path.
+    pub fn for_finding(finding: &LintFinding) -> Self {
+        Self {
+            lint: finding.lint.name().to_string(),
+            mods: finding.mods.iter().cloned().collect(),
+            path: finding.path.clone(),
+            all_paths: false,
+        }
+    }
+
+    /// Suppresses every finding of the lint of `finding` about the same mods.
+    pub fn for_lint(finding: &LintFinding) -> Self {
+        Self {
+            path: None,
+            all_paths: true,
+            ..Self::for_finding(finding)
+        }
+    }
+
+    pub fn matches(&self, finding: &LintFinding) -> bool {
+        self.lint == finding.lint.name()
+            && finding.mods.iter().cloned().collect::<BTreeSet<_>>() == self.mods
+            && (self.all_paths || self.path == finding.path)
+    }
+}
+
+/// Identifies a finding across lint runs, to tell new findings apart from those of a baseline.
+/// Messages aren't part of it since they can change for the same finding, e.g. when a mod is
+/// updated.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LintFindingKey {
+    pub lint: String,
+    /// Mods the finding is about, in no particular order.
+    pub mods: BTreeSet<ModSpecification>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl LintFinding {
+    pub fn key(&self) -> LintFindingKey {
+        LintFindingKey {
+            lint: self.lint.name().to_string(),
+            mods: self.mods.iter().cloned().collect(),
+            path: self.path.clone(),
+        }
+    }
+
+    /// Whether the finding isn't part of `baseline`. Without a baseline no finding is new.
+    pub fn is_new(&self, baseline: Option<&BTreeSet<LintFindingKey>>) -> bool {
+        baseline.is_some_and(|baseline| !baseline.contains(&self.key()))
+    }
+}
+
+impl LintReport {
+    /// Moves the findings matching `suppression` to the suppressed findings.
+    pub fn suppress(&mut self, suppression: &LintSuppression) {
+        let (suppressed, findings) = std::mem::take(&mut self.findings)
+            .into_iter()
+            .partition::<Vec<_>, _>(|f| suppression.matches(f));
+        self.findings = findings;
+        self.suppressed.extend(suppressed);
+    }
+
+    /// Keys of the reported findings, to be compared against later reports.
+    pub fn baseline(&self) -> BTreeSet<LintFindingKey> {
+        self.findings.iter().map(LintFinding::key).collect()
+    }
+}
@@ -1,18 +1,27 @@
-use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet};
-use std::io::BufReader;
-use std::path::PathBuf;
 
-use anyhow::{bail, Result};
-use fs_err as fs;
-use path_slash::PathExt;
-use rayon::prelude::*;
-use sha2::Digest;
+use anyhow::Result;
 use tracing::trace;
 
 use crate::providers::ModSpecification;
 
-use super::{Lint, LintCtxt};
+use super::{resolve_path, Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const UNMODIFIED_GAME_ASSETS: Self = LintId::new("unmodified_game_assets");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::UNMODIFIED_GAME_ASSETS,
+        title: "Mods containing unmodified game assets",
+        description: "Files identical to the ones shipped with the game, which only make conflicts more likely.",
+        severity: Severity::Warning,
+        requires_game_pak: true,
+        fixable: true,
+        check: findings,
+    }
+}
 
 #[derive(Default)]
 pub struct UnmodifiedGameAssetsLint;
@@ -21,74 +30,33 @@ impl Lint for UnmodifiedGameAssetsLint {
     type Output = BTreeMap<ModSpecification, BTreeSet<String>>;
 
     fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output> {
-        let Some(game_pak_path) = &lcx.fsd_pak_path else {
-            bail!("UnmodifiedGameAssetsLint requires specifying a valid game pak path");
-        };
-
-        // Adapted from
-        // <https://github.com/trumank/repak/blob/a006d9ed6f021687a87b8b2ff9d66083d019824c/repak_cli/src/main.rs#L217>.
-        let mut reader = BufReader::new(fs::File::open(game_pak_path)?);
-        let pak = repak::PakBuilder::new().reader(&mut reader)?;
-
-        let mount_point = PathBuf::from(pak.mount_point());
-
-        let full_paths = pak
-            .files()
-            .into_iter()
-            .map(|f| (mount_point.join(&f), f))
-            .collect::<Vec<_>>();
-        let stripped = full_paths
-            .iter()
-            .map(|(full_path, _path)| full_path.strip_prefix("../../../"))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let game_file_hashes: std::sync::Arc<
-            std::sync::Mutex<BTreeMap<std::borrow::Cow<'_, str>, Vec<u8>>>,
-        > = Default::default();
-
-        full_paths.par_iter().zip(stripped).try_for_each_init(
-            || (game_file_hashes.clone(), fs::File::open(game_pak_path)),
-            |(hashes, file), ((_full_path, path), stripped)| -> Result<(), repak::Error> {
-                let mut hasher = sha2::Sha256::new();
-                pak.read_file(
-                    path,
-                    &mut BufReader::new(file.as_ref().unwrap()),
-                    &mut hasher,
-                )?;
-                let hash = hasher.finalize();
-                hashes
-                    .lock()
-                    .unwrap()
-                    .insert(stripped.to_slash_lossy(), hash.to_vec());
-                Ok(())
-            },
-        )?;
-
-        let mut unmodified_game_assets = BTreeMap::new();
-
-        lcx.for_each_mod_file(
-            |mod_spec, mut pak_read_seek, pak_reader, _, normalized_path| {
-                if let Some(reference_hash) = game_file_hashes
-                    .lock()
-                    .unwrap()
-                    .get(&Cow::Owned(normalized_path.clone()))
-                {
-                    let mut hasher = sha2::Sha256::new();
-                    pak_reader.read_file(&normalized_path, &mut pak_read_seek, &mut hasher)?;
-                    let mod_file_hash = hasher.finalize().to_vec();
-
-                    if &mod_file_hash == reference_hash {
+        let game_pak_index = lcx.game_pak_index()?;
+
+        let mut unmodified_game_assets = BTreeMap::<_, BTreeSet<_>>::new();
+
+        lcx.for_each_mod(
+            |mod_spec, pak_read_seek, pak_reader| {
+                for p in pak_reader.files() {
+                    let Ok(path) = resolve_path(pak_reader.mount_point(), &p) else {
+                        continue;
+                    };
+                    let normalized_path = path.to_ascii_lowercase();
+                    if game_pak_index.get(&normalized_path).is_none() {
+                        continue;
+                    }
+                    let data = pak_reader.get(&p, pak_read_seek)?;
+                    if game_pak_index.is_unmodified(&normalized_path, &data) {
                         unmodified_game_assets
-                            .entry(mod_spec)
-                            .and_modify(|paths: &mut BTreeSet<String>| {
-                                paths.insert(normalized_path.clone());
-                            })
-                            .or_insert_with(|| [normalized_path].into());
+                            .entry(mod_spec.clone())
+                            .or_default()
+                            .insert(normalized_path);
                     }
                 }
-
                 Ok(())
             },
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
         )?;
 
         trace!("unmodified_game_assets:\n{:#?}", unmodified_game_assets);
@@ -96,3 +64,21 @@ impl Lint for UnmodifiedGameAssetsLint {
         Ok(unmodified_game_assets)
     }
 }
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(UnmodifiedGameAssetsLint
+        .check_mods(lcx)?
+        .into_iter()
+        .flat_map(|(mod_spec, paths)| {
+            paths.into_iter().map(move |path| {
+                let message = format!("{} includes unmodified game asset `{path}`", mod_spec.url);
+                LintFinding::new(
+                    LintId::UNMODIFIED_GAME_ASSETS,
+                    mod_spec.clone(),
+                    Some(path),
+                    message,
+                )
+            })
+        })
+        .collect())
+}
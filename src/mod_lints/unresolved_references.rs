@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::{BufReader, Cursor};
+
+use anyhow::{bail, Result};
+use fs_err as fs;
+use rayon::prelude::*;
+use unreal_asset::{engine_version::EngineVersion, Asset};
+
+use crate::providers::ModSpecification;
+
+use super::{resolve_path, Lint, LintCtxt, LintDefinition, LintFinding, LintId, Severity};
+
+impl LintId {
+    pub const UNRESOLVED_REFERENCES: Self = LintId::new("unresolved_references");
+}
+
+inventory::submit! {
+    LintDefinition {
+        id: LintId::UNRESOLVED_REFERENCES,
+        title: "Mods referencing missing assets",
+        description: "Assets importing `/Game` packages that aren't in the game, the mod itself or any other enabled mod, usually because a framework mod they depend on is missing.",
+        severity: Severity::Error,
+        requires_game_pak: true,
+        fixable: false,
+        check: findings,
+    }
+}
+
+#[derive(Default)]
+pub struct UnresolvedReferencesLint;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnresolvedReference {
+    /// Lowercase paths of the assets importing the package.
+    pub referenced_by: BTreeSet<String>,
+    /// Mods that aren't enabled but contain the package.
+    pub candidates: BTreeSet<ModSpecification>,
+}
+
+/// Maps a lowercase path relative to the game root to the lowercase name of the package it
+/// contains, e.g. `fsd/content/foo/bar.uasset` to `/game/foo/bar`.
+fn package_name(path: &str) -> Option<String> {
+    let stem = path
+        .strip_suffix(".uasset")
+        .or_else(|| path.strip_suffix(".umap"))?;
+    Some(format!("/game/{}", stem.strip_prefix("fsd/content/")?))
+}
+
+/// Lowercase names of the packages contained in a pak.
+fn packages(pak_reader: &repak::PakReader) -> HashSet<String> {
+    pak_reader
+        .files()
+        .into_iter()
+        .filter_map(|p| resolve_path(pak_reader.mount_point(), &p).ok())
+        .filter_map(|p| package_name(&p.to_ascii_lowercase()))
+        .collect()
+}
+
+/// Lowercase names of the `/Game` packages imported by an asset, or `None` if it can't be parsed.
+fn imported_packages(uasset: &[u8], uexp: &[u8]) -> Option<BTreeSet<String>> {
+    // malformed assets can make the parser panic, they are reported by the corrupt assets lint
+    let asset = std::panic::catch_unwind(|| {
+        Asset::new(
+            Cursor::new(uasset),
+            Some(Cursor::new(uexp)),
+            EngineVersion::VER_UE4_27,
+            None,
+        )
+        .ok()
+    })
+    .ok()
+    .flatten()?;
+    Some(
+        asset
+            .imports
+            .iter()
+            .filter(|import| {
+                import.outer_index.index == 0 && import.class_name.get_owned_content() == "Package"
+            })
+            .map(|import| import.object_name.get_owned_content().to_ascii_lowercase())
+            .filter(|name| name.starts_with("/game/"))
+            .collect(),
+    )
+}
+
+impl Lint for UnresolvedReferencesLint {
+    type Output = BTreeMap<ModSpecification, BTreeMap<String, UnresolvedReference>>;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output> {
+        let Some(game_pak_path) = &lcx.fsd_pak_path else {
+            bail!("UnresolvedReferencesLint requires specifying a valid game pak path");
+        };
+
+        let mut reader = BufReader::new(fs::File::open(game_pak_path)?);
+        let game_pak = repak::PakBuilder::new().reader(&mut reader)?;
+        let game_packages = packages(&game_pak);
+
+        // packages provided by any enabled mod, and the packages each mod imports by asset
+        let mut provided = HashSet::new();
+        let mut mod_references = vec![];
+
+        lcx.for_each_mod(
+            |mod_spec, pak_read_seek, pak_reader| {
+                provided.extend(packages(pak_reader));
+
+                let files = pak_reader.files().into_iter().collect::<HashSet<_>>();
+                let mut pairs = vec![];
+                for uasset_path in &files {
+                    let Some(stem) = uasset_path
+                        .strip_suffix(".uasset")
+                        .or_else(|| uasset_path.strip_suffix(".umap"))
+                    else {
+                        continue;
+                    };
+                    let uexp_path = format!("{stem}.uexp");
+                    if !files.contains(&uexp_path) {
+                        continue;
+                    }
+                    let Ok(path) = resolve_path(pak_reader.mount_point(), uasset_path) else {
+                        continue;
+                    };
+                    pairs.push((
+                        path.to_ascii_lowercase(),
+                        pak_reader.get(uasset_path, pak_read_seek)?,
+                        pak_reader.get(&uexp_path, pak_read_seek)?,
+                    ));
+                }
+
+                let references = pairs
+                    .par_iter()
+                    .filter_map(|(path, uasset, uexp)| {
+                        imported_packages(uasset, uexp).map(|imports| (path.clone(), imports))
+                    })
+                    .collect::<Vec<_>>();
+                mod_references.push((mod_spec, references));
+                Ok(())
+            },
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+        )?;
+
+        let mut candidates = BTreeMap::<String, BTreeSet<ModSpecification>>::new();
+        lcx.for_each_candidate_mod(|mod_spec, _, pak_reader| {
+            for package in packages(pak_reader) {
+                candidates
+                    .entry(package)
+                    .or_default()
+                    .insert(mod_spec.clone());
+            }
+            Ok(())
+        })?;
+
+        // mods with several paks have an entry per pak
+        let mut unresolved_mods = BTreeMap::<_, BTreeMap<String, UnresolvedReference>>::new();
+        for (mod_spec, references) in mod_references {
+            for (path, imports) in references {
+                for package in imports {
+                    if game_packages.contains(&package) || provided.contains(&package) {
+                        continue;
+                    }
+                    let reference = unresolved_mods
+                        .entry(mod_spec.clone())
+                        .or_default()
+                        .entry(package.clone())
+                        .or_insert_with(|| UnresolvedReference {
+                            candidates: candidates.get(&package).cloned().unwrap_or_default(),
+                            ..Default::default()
+                        });
+                    reference.referenced_by.insert(path.clone());
+                }
+            }
+        }
+
+        Ok(unresolved_mods)
+    }
+}
+
+fn findings(lcx: &LintCtxt) -> Result<Vec<LintFinding>> {
+    Ok(UnresolvedReferencesLint
+        .check_mods(lcx)?
+        .into_iter()
+        .flat_map(|(mod_spec, unresolved)| {
+            unresolved.into_iter().map(move |(package, reference)| {
+                let referenced_by = reference
+                    .referenced_by
+                    .iter()
+                    .map(|p| format!("`{p}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut message = format!(
+                    "`{package}` referenced by {referenced_by} in {} isn't in the game or any enabled mod",
+                    mod_spec.url
+                );
+                if !reference.candidates.is_empty() {
+                    let candidates = reference
+                        .candidates
+                        .iter()
+                        .map(|m| m.url.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    message.push_str(&format!("; enable one of these mods to provide it: {candidates}"));
+                }
+                LintFinding::new(
+                    LintId::UNRESOLVED_REFERENCES,
+                    mod_spec.clone(),
+                    Some(package),
+                    message,
+                )
+            })
+        })
+        .collect())
+}
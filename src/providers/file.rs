@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::Result;
+use mint_lib::GameVersion;
 use tokio::sync::mpsc::Sender;
 
 use super::{
@@ -51,6 +52,7 @@ impl ModProvider for FileProvider {
             name,
             spec: spec.clone(),
             versions: vec![],
+            version: None,
             resolution: ModResolution::unresolvable(
                 path.to_string_lossy().to_string(),
                 path.file_name()
@@ -101,6 +103,7 @@ impl ModProvider for FileProvider {
             name,
             spec: spec.clone(),
             versions: vec![],
+            version: None,
             resolution: ModResolution::unresolvable(
                 path.to_string_lossy().to_string(),
                 path.file_name()
@@ -120,4 +123,12 @@ impl ModProvider for FileProvider {
     fn get_version_name(&self, _spec: &ModSpecification, _cache: ProviderCache) -> Option<String> {
         Some("latest".to_string())
     }
+    fn compatible_version(
+        &self,
+        _spec: &ModSpecification,
+        _game_version: &GameVersion,
+        _cache: ProviderCache,
+    ) -> Option<ModSpecification> {
+        None
+    }
 }
@@ -3,6 +3,7 @@ use std::sync::OnceLock;
 use std::{collections::HashMap, sync::Arc};
 
 use anyhow::{bail, Result};
+use mint_lib::GameVersion;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
 use tracing::info;
@@ -87,6 +88,7 @@ impl ModProvider for HttpProvider {
             name,
             spec: spec.clone(),
             versions: vec![],
+            version: None,
             resolution: ModResolution::resolvable(spec.url.to_owned()),
             suggested_require: false,
             suggested_dependencies: vec![],
@@ -197,6 +199,7 @@ impl ModProvider for HttpProvider {
             name,
             spec: spec.clone(),
             versions: vec![],
+            version: None,
             resolution: ModResolution::resolvable(spec.url.to_owned()),
             suggested_require: false,
             suggested_dependencies: vec![],
@@ -211,4 +214,12 @@ impl ModProvider for HttpProvider {
     fn get_version_name(&self, _spec: &ModSpecification, _cache: ProviderCache) -> Option<String> {
         Some("latest".to_string())
     }
+    fn compatible_version(
+        &self,
+        _spec: &ModSpecification,
+        _game_version: &GameVersion,
+        _cache: ProviderCache,
+    ) -> Option<ModSpecification> {
+        None
+    }
 }
@@ -7,6 +7,7 @@ use crate::state::config::ConfigWrapper;
 
 use anyhow::{Context, Result};
 use fs_err as fs;
+use mint_lib::GameVersion;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
 use tracing::info;
@@ -276,6 +277,14 @@ impl ModStore {
         Ok(())
     }
 
+    /// Writes `blob` to the blob cache and returns its path, such as for mods modified by mint.
+    pub fn store_blob(&self, blob: &[u8]) -> Result<PathBuf> {
+        let blob = self.blob_cache.write(blob)?;
+        self.blob_cache
+            .get_path(&blob)
+            .context("blob missing after writing it")
+    }
+
     pub fn get_mod_info(&self, spec: &ModSpecification) -> Option<ModInfo> {
         self.get_provider(&spec.url)
             .ok()?
@@ -293,6 +302,18 @@ impl ModStore {
             .unwrap()
             .get_version_name(spec, self.cache.clone())
     }
+
+    pub fn compatible_version(
+        &self,
+        spec: &ModSpecification,
+        game_version: &GameVersion,
+    ) -> Option<ModSpecification> {
+        self.get_provider(&spec.url).ok()?.compatible_version(
+            spec,
+            game_version,
+            self.cache.clone(),
+        )
+    }
 }
 
 fn read_cache_metadata_or_default(cache_metadata_path: &PathBuf) -> Result<VersionAnnotatedCache> {
@@ -381,6 +402,7 @@ pub struct ModInfo {
     pub name: String,
     pub spec: ModSpecification,          // unpinned version
     pub versions: Vec<ModSpecification>, // pinned versions TODO make this a different type
+    pub version: Option<String>,         // version name of the resolved file, if known
     pub resolution: ModResolution,
     pub suggested_require: bool,
     pub suggested_dependencies: Vec<ModSpecification>, // ModResponse
@@ -490,6 +512,14 @@ pub trait ModProvider: Send + Sync {
     fn get_mod_info(&self, spec: &ModSpecification, cache: ProviderCache) -> Option<ModInfo>;
     fn is_pinned(&self, spec: &ModSpecification, cache: ProviderCache) -> bool;
     fn get_version_name(&self, spec: &ModSpecification, cache: ProviderCache) -> Option<String>;
+    /// Pinned specification of the newest version of an unpinned mod made for `game_version`, if
+    /// the latest version isn't. Relies on the resolved mod being cached.
+    fn compatible_version(
+        &self,
+        spec: &ModSpecification,
+        game_version: &GameVersion,
+        cache: ProviderCache,
+    ) -> Option<ModSpecification>;
 }
 
 #[derive(Clone)]
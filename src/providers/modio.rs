@@ -7,6 +7,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use mockall::{automock, predicate::*};
 
 use anyhow::{bail, Context, Result};
+use mint_lib::GameVersion;
 use reqwest::{Request, Response};
 use reqwest_middleware::{Middleware, Next};
 use serde::{Deserialize, Serialize};
@@ -404,11 +405,12 @@ impl<M: DrgModio + Send + Sync> ModProvider for ModioProvider<M> {
         let url = &spec.url;
         let captures = re_mod().captures(url).context("invalid modio URL {url}")?;
 
-        if let (Some(mod_id), Some(_modfile_id)) =
+        if let (Some(mod_id), Some(modfile_id)) =
             (captures.name("mod_id"), captures.name("modfile_id"))
         {
             // both mod ID and modfile ID specified, but not necessarily name
             let mod_id = mod_id.as_str().parse::<u32>().unwrap();
+            let modfile_id = modfile_id.as_str().parse::<u32>().unwrap();
 
             let mod_ =
                 if let Some(mod_) = read_cache(&cache, update, |c| c.mods.get(&mod_id).cloned()) {
@@ -489,6 +491,11 @@ impl<M: DrgModio + Send + Sync> ModProvider for ModioProvider<M> {
                 provider: MODIO_PROVIDER_ID,
                 spec: format_spec(&mod_.name_id, mod_id, None),
                 name: mod_.name,
+                version: mod_
+                    .modfiles
+                    .iter()
+                    .find(|f| f.id == modfile_id)
+                    .and_then(|f| f.version.clone()),
                 versions: mod_
                     .modfiles
                     .into_iter()
@@ -796,10 +803,17 @@ impl<M: DrgModio + Send + Sync> ModProvider for ModioProvider<M> {
             })
             .collect::<Option<Vec<_>>>()?;
 
+        let version = captures
+            .name("modfile_id")
+            .and_then(|id| id.as_str().parse::<u32>().ok())
+            .and_then(|id| mod_.modfiles.iter().find(|f| f.id == id))
+            .and_then(|f| f.version.clone());
+
         Some(ModInfo {
             provider: MODIO_PROVIDER_ID,
             spec: format_spec(&mod_.name_id, mod_id, None),
             name: mod_.name.clone(),
+            version,
             versions: mod_
                 .modfiles
                 .iter()
@@ -857,6 +871,45 @@ impl<M: DrgModio + Send + Sync> ModProvider for ModioProvider<M> {
             None
         }
     }
+    fn compatible_version(
+        &self,
+        spec: &ModSpecification,
+        game_version: &GameVersion,
+        cache: ProviderCache,
+    ) -> Option<ModSpecification> {
+        let captures = re_mod().captures(&spec.url)?;
+        if captures.name("modfile_id").is_some() {
+            return None;
+        }
+
+        let cache = cache.read().unwrap();
+        let prov = cache.get::<ModioCache>(MODIO_PROVIDER_ID)?;
+
+        let mod_id = if let Some(mod_id) = captures.name("mod_id") {
+            mod_id.as_str().parse::<u32>().ok()
+        } else if let Some(name_id) = captures.name("name_id") {
+            prov.mod_id_map.get(name_id.as_str()).cloned()
+        } else {
+            None
+        }?;
+        let mod_ = prov.mods.get(&mod_id)?;
+
+        // mod.io only tags whole mods with game versions, so the latest modfile is assumed to be
+        // the one made for them and older ones are told apart by their version names
+        let versions = process_modio_tags(&mod_.tags).versions;
+        if versions.is_empty() || versions.iter().any(|v| game_version.matches_tag(v)) {
+            return None;
+        }
+        mod_.modfiles
+            .iter()
+            .filter(|f| {
+                f.version
+                    .as_deref()
+                    .is_some_and(|v| game_version.matches_tag(v))
+            })
+            .max_by_key(|f| (f.date_added, f.id))
+            .map(|f| format_spec(&mod_.name_id, mod_id, Some(f.id)))
+    }
 }
 
 fn process_modio_tags(set: &HashSet<String>) -> ModioTags {
@@ -1016,4 +1069,70 @@ mod test {
                 .collect()
         );
     }
+
+    #[test]
+    fn test_compatible_version() {
+        let modfile = |id, date_added, version: &str| ModioFile {
+            id,
+            date_added,
+            version: Some(version.to_string()),
+            changelog: None,
+        };
+        let cache = Arc::new(RwLock::new(ConfigWrapper::<VersionAnnotatedCache>::memory(
+            VersionAnnotatedCache::default(),
+        )));
+        {
+            let mut lock = cache.write().unwrap();
+            let modio_cache = lock.get_mut::<ModioCache>(MODIO_PROVIDER_ID);
+            modio_cache.mod_id_map.insert("test-mod".to_string(), 3);
+            modio_cache.mods.insert(
+                3,
+                ModioMod {
+                    name_id: "test-mod".to_string(),
+                    name: "Test Mod".to_string(),
+                    latest_modfile: Some(4),
+                    modfiles: vec![
+                        modfile(1, 100, "1.38"),
+                        modfile(2, 200, "1.38"),
+                        modfile(3, 300, "1.39"),
+                        modfile(4, 400, "2.0"),
+                    ],
+                    tags: ["1.39".to_string()].into_iter().collect(),
+                },
+            );
+        }
+
+        let modio_provider = ModioProvider::new(MockDrgModio::new());
+        let compatible = |url: &str, version: &str| {
+            modio_provider.compatible_version(
+                &ModSpecification::new(url.to_string()),
+                &GameVersion(version.to_string()),
+                cache.clone(),
+            )
+        };
+
+        assert_eq!(
+            compatible("https://mod.io/g/drg/m/test-mod", "1.38.89736.0"),
+            Some(format_spec("test-mod", 3, Some(2)))
+        );
+        assert_eq!(
+            compatible("https://mod.io/g/drg/m/test-mod#3", "1.38.89736.0"),
+            Some(format_spec("test-mod", 3, Some(2)))
+        );
+        // the latest modfile is made for the installed version
+        assert_eq!(
+            compatible("https://mod.io/g/drg/m/test-mod", "1.39.90000.0"),
+            None
+        );
+        // pinned mods are left alone
+        assert_eq!(
+            compatible("https://mod.io/g/drg/m/test-mod#3/1", "1.38.89736.0"),
+            None
+        );
+        // no modfile is named after the installed version
+        assert_eq!(
+            compatible("https://mod.io/g/drg/m/test-mod", "1.37.80000.0"),
+            None
+        );
+    }
 }
@@ -1,9 +1,9 @@
 pub mod config;
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     ops::{Deref, DerefMut},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -11,9 +11,12 @@ use anyhow::{bail, Context, Result};
 use fs_err as fs;
 use mint_lib::DRGInstallation;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 use crate::{
     gui::GuiTheme,
+    integrate::{BundleCompression, IntegrationOptions},
+    mod_lints::{LintFindingKey, LintSuppression},
     providers::{ModInfo, ModSpecification, ModStore},
     Dirs,
 };
@@ -31,6 +34,73 @@ pub struct ModConfig {
     pub enabled: bool,
     #[serde(default, skip_serializing_if = "is_zero")]
     pub priority: i32,
+    /// Paths of paks inside the mod archive that should not be integrated.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub disabled_paks: BTreeSet<String>,
+    /// Settings passed to the mod in game through the integration asset.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub settings: BTreeMap<String, String>,
+    /// Copy of the mod cleaned by the lint fixes, read instead of the mod's own file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed_pak: Option<FixedPak>,
+}
+
+/// Pak written to the blob cache by the lint fixes without the files they reported.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixedPak {
+    /// File of the mod the pak was cleaned from, so it isn't used once the mod is updated.
+    pub source: PathBuf,
+    /// Path of the cleaned pak in the blob cache.
+    pub path: PathBuf,
+}
+
+impl ModConfig {
+    pub fn new(spec: ModSpecification) -> Self {
+        Self {
+            spec,
+            required: false,
+            enabled: true,
+            priority: 0,
+            disabled_paks: Default::default(),
+            settings: Default::default(),
+            fixed_pak: None,
+        }
+    }
+
+    /// Path of the file to read the mod from, given the file fetched for its spec. This is the
+    /// cleaned pak if one is set, unless the mod was updated since or the pak is no longer in the
+    /// cache.
+    pub fn file_path(&self, fetched: &Path) -> PathBuf {
+        match &self.fixed_pak {
+            None => fetched.to_path_buf(),
+            Some(fixed_pak) if fixed_pak.source != fetched => {
+                warn!(
+                    "{} changed since it was cleaned, using it without the lint fixes",
+                    self.spec.url
+                );
+                fetched.to_path_buf()
+            }
+            Some(fixed_pak) if !fixed_pak.path.exists() => {
+                warn!(
+                    "cleaned pak {} of {} is missing, using the original mod",
+                    fixed_pak.path.display(),
+                    self.spec.url
+                );
+                fetched.to_path_buf()
+            }
+            Some(fixed_pak) => fixed_pak.path.clone(),
+        }
+    }
+
+    /// Uses the pak cleaned from the file at `linted` instead of the mod's own file. If `linted`
+    /// is already a cleaned pak, the new one replaces it.
+    pub fn use_fixed_pak(&mut self, path: PathBuf, linted: &Path) {
+        let source = match &self.fixed_pak {
+            Some(fixed_pak) if fixed_pak.path == linted => fixed_pak.source.clone(),
+            _ => linted.to_path_buf(),
+        };
+        self.fixed_pak = Some(FixedPak { source, path });
+    }
 }
 
 fn default_true() -> bool {
@@ -59,6 +129,21 @@ pub struct ModProfile {
     #[obake(cfg("0.1.0"))]
     #[obake(cfg("0.2.0"))]
     pub mods: Vec<ModOrGroup>,
+
+    /// IDs of integration patches disabled for this profile.
+    #[obake(cfg("0.2.0"))]
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub disabled_patches: BTreeSet<String>,
+
+    /// Lint findings that are expected for this profile and not reported.
+    #[obake(cfg("0.2.0"))]
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub lint_suppressions: BTreeSet<LintSuppression>,
+
+    /// Findings of the lint report saved as baseline. Findings not in it are highlighted as new.
+    #[obake(cfg("0.2.0"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lint_baseline: Option<BTreeSet<LintFindingKey>>,
 }
 
 #[derive(Debug, Clone, Hash, Serialize, Deserialize)]
@@ -79,6 +164,9 @@ impl From<ModProfile!["0.1.0"]> for ModProfile!["0.2.0"] {
     fn from(legacy: ModProfile!["0.1.0"]) -> Self {
         Self {
             mods: legacy.mods,
+            disabled_patches: Default::default(),
+            lint_suppressions: Default::default(),
+            lint_baseline: None,
         }
     }
 }
@@ -453,6 +541,12 @@ pub struct Config {
     pub confirm_deletion: bool,
     #[serde(default = "default_true")]
     pub show_changelog: bool,
+    #[serde(default)]
+    pub bundle_compression: BundleCompression,
+    #[serde(default)]
+    pub merge_datatables: bool,
+    #[serde(default)]
+    pub prefer_compatible_modfiles: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -533,6 +627,9 @@ impl Default for Config!["0.0.0"] {
             sorting_config: None,
             confirm_deletion: true,
             show_changelog: true,
+            bundle_compression: Default::default(),
+            merge_datatables: false,
+            prefer_compatible_modfiles: false,
         }
     }
 }
@@ -548,6 +645,20 @@ impl Default for Config!["0.1.0"] {
             sorting_config: None,
             confirm_deletion: true,
             show_changelog: true,
+            bundle_compression: Default::default(),
+            merge_datatables: false,
+            prefer_compatible_modfiles: false,
+        }
+    }
+}
+
+impl Config!["0.1.0"] {
+    pub fn integration_options(&self) -> IntegrationOptions {
+        IntegrationOptions {
+            compression: self.bundle_compression,
+            merge_datatables: self.merge_datatables,
+            prefer_compatible_modfiles: self.prefer_compatible_modfiles,
+            ..Default::default()
         }
     }
 }
@@ -561,6 +672,9 @@ impl From<Config!["0.0.0"]> for Config!["0.1.0"] {
             sorting_config: legacy.sorting_config,
             confirm_deletion: legacy.confirm_deletion,
             show_changelog: legacy.show_changelog,
+            bundle_compression: legacy.bundle_compression,
+            merge_datatables: legacy.merge_datatables,
+            prefer_compatible_modfiles: legacy.prefer_compatible_modfiles,
         }
     }
 }
@@ -703,6 +817,9 @@ mod mod_data_tests {
             required: false,
             enabled: false,
             priority: 50,
+            disabled_paks: Default::default(),
+            settings: Default::default(),
+            fixed_pak: None,
         };
 
         let mod_2 = ModConfig {
@@ -710,6 +827,9 @@ mod mod_data_tests {
             required: true,
             enabled: false,
             priority: 50,
+            disabled_paks: Default::default(),
+            settings: Default::default(),
+            fixed_pak: None,
         };
 
         let mod_3 = ModConfig {
@@ -717,6 +837,9 @@ mod mod_data_tests {
             required: false,
             enabled: true,
             priority: 50,
+            disabled_paks: Default::default(),
+            settings: Default::default(),
+            fixed_pak: None,
         };
 
         let mod_data = ModData {
@@ -731,6 +854,9 @@ mod mod_data_tests {
                             enabled: false,
                         },
                     ],
+                    disabled_patches: Default::default(),
+                    lint_suppressions: Default::default(),
+                    lint_baseline: None,
                 },
             )]
             .into(),
@@ -757,6 +883,9 @@ mod mod_data_tests {
             required: false,
             enabled: false,
             priority: 50,
+            disabled_paks: Default::default(),
+            settings: Default::default(),
+            fixed_pak: None,
         };
 
         let mod_2 = ModConfig {
@@ -764,6 +893,9 @@ mod mod_data_tests {
             required: true,
             enabled: false,
             priority: 50,
+            disabled_paks: Default::default(),
+            settings: Default::default(),
+            fixed_pak: None,
         };
 
         let mod_3 = ModConfig {
@@ -771,6 +903,9 @@ mod mod_data_tests {
             required: false,
             enabled: true,
             priority: 50,
+            disabled_paks: Default::default(),
+            settings: Default::default(),
+            fixed_pak: None,
         };
 
         let mod_data = ModData {
@@ -785,6 +920,9 @@ mod mod_data_tests {
                             enabled: true,
                         },
                     ],
+                    disabled_patches: Default::default(),
+                    lint_suppressions: Default::default(),
+                    lint_baseline: None,
                 },
             )]
             .into(),
@@ -811,6 +949,9 @@ mod mod_data_tests {
             required: false,
             enabled: false,
             priority: 50,
+            disabled_paks: Default::default(),
+            settings: Default::default(),
+            fixed_pak: None,
         };
 
         let mod_2 = ModConfig {
@@ -818,6 +959,9 @@ mod mod_data_tests {
             required: true,
             enabled: false,
             priority: 50,
+            disabled_paks: Default::default(),
+            settings: Default::default(),
+            fixed_pak: None,
         };
 
         let mod_3 = ModConfig {
@@ -825,6 +969,9 @@ mod mod_data_tests {
             required: false,
             enabled: true,
             priority: 50,
+            disabled_paks: Default::default(),
+            settings: Default::default(),
+            fixed_pak: None,
         };
 
         let mod_data = ModData {
@@ -839,6 +986,9 @@ mod mod_data_tests {
                             enabled: true,
                         },
                     ],
+                    disabled_patches: Default::default(),
+                    lint_suppressions: Default::default(),
+                    lint_baseline: None,
                 },
             )]
             .into(),
@@ -854,4 +1004,30 @@ mod mod_data_tests {
         let any_required = mod_data.any_mod("default", |mc, _| mc.required);
         assert!(any_required);
     }
+
+    #[test]
+    fn test_fixed_pak() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetched = dir.path().join("mod.pak");
+        let fixed_1 = dir.path().join("fixed_1");
+        let fixed_2 = dir.path().join("fixed_2");
+
+        let mut mc = ModConfig::new(ModSpecification::new("a".to_string()));
+        assert_eq!(mc.file_path(&fetched), fetched);
+
+        // not used while missing from the cache
+        mc.use_fixed_pak(fixed_1.clone(), &fetched);
+        assert_eq!(mc.file_path(&fetched), fetched);
+        std::fs::write(&fixed_1, b"").unwrap();
+        assert_eq!(mc.file_path(&fetched), fixed_1);
+
+        // fixing the cleaned pak again keeps track of the mod's own file
+        mc.use_fixed_pak(fixed_2.clone(), &fixed_1);
+        std::fs::write(&fixed_2, b"").unwrap();
+        assert_eq!(mc.file_path(&fetched), fixed_2);
+
+        // not used once the mod is updated
+        let updated = dir.path().join("updated.pak");
+        assert_eq!(mc.file_path(&updated), updated);
+    }
 }
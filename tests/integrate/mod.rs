@@ -0,0 +1,177 @@
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use mint::integrate::{BuildTarget, IntegrationOptions, TargetOutput, TargetPreset};
+use mint::mod_lints::{LintId, Severity};
+use mint::providers::{ModInfo, ModResolution, ModSpecification, ResolvableStatus};
+use mint::state::ModConfig;
+
+use crate::lint::write_mod_pak;
+
+/// Writes a game pak laid out like an installation under `dir` and returns its path.
+fn write_game_pak(dir: &Path, files: &[(&str, &[u8])]) -> PathBuf {
+    let paks = dir.join("game/FSD/Content/Paks");
+    std::fs::create_dir_all(&paks).unwrap();
+    write_mod_pak(&paks, "FSD-WindowsNoEditor.pak", "../../../", files)
+}
+
+fn mod_info(spec: &ModSpecification) -> ModInfo {
+    ModInfo {
+        provider: "file",
+        name: spec.url.clone(),
+        spec: spec.clone(),
+        versions: vec![],
+        version: None,
+        resolution: ModResolution {
+            url: spec.url.clone(),
+            status: ResolvableStatus::Resolvable,
+        },
+        suggested_require: false,
+        suggested_dependencies: vec![],
+        modio_tags: None,
+        modio_id: None,
+    }
+}
+
+/// Options writing the bundle to `out` without the hook.
+fn options(out: &Path) -> IntegrationOptions {
+    IntegrationOptions {
+        target: BuildTarget {
+            output: TargetOutput::Directory(out.to_path_buf()),
+            preset: TargetPreset::Server,
+        },
+        ..Default::default()
+    }
+}
+
+/// Reads every file of the bundle written to `out`.
+fn read_bundle(out: &Path) -> Vec<(String, Vec<u8>)> {
+    let mut reader =
+        BufReader::new(std::fs::File::open(out.join("FSD/Content/Paks/mods_P.pak")).unwrap());
+    let pak = repak::PakBuilder::new().reader(&mut reader).unwrap();
+    pak.files()
+        .into_iter()
+        .map(|p| {
+            let data = pak.get(&p, &mut reader).unwrap();
+            (p, data)
+        })
+        .collect()
+}
+
+#[test]
+pub fn test_integrate_mod_overriding_integration_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let game_pak = write_game_pak(dir.path(), &[("FSD/Content/Game/Other.uasset", b"")]);
+
+    let spec = ModSpecification {
+        url: "overriding".to_string(),
+    };
+    let mod_path = write_mod_pak(
+        dir.path(),
+        "overriding.pak",
+        "../../../",
+        &[
+            (
+                "FSD/Content/_AssemblyStorm/ModIntegration/MI_SpawnMods.uasset",
+                b"mod",
+            ),
+            ("FSD/Content/Mod/Asset.uasset", b"asset"),
+        ],
+    );
+
+    let out = dir.path().join("out");
+    let report = mint::integrate::integrate(
+        &game_pak,
+        vec![(mod_info(&spec), ModConfig::new(spec.clone()), mod_path)],
+        &options(&out),
+    )
+    .unwrap();
+
+    // patched game assets missing from the game are reported instead of failing
+    assert!(!report.ineffective_patches.is_empty());
+    assert!(report
+        .ineffective_patches
+        .iter()
+        .all(|p| p.reason == "asset not found in game or mods"));
+
+    let bundle = read_bundle(&out);
+    let get = |path: &str| {
+        let files = bundle.iter().filter(|(p, _)| p == path).collect::<Vec<_>>();
+        assert_eq!(files.len(), 1, "{path} should be in the bundle once");
+        files[0].1.clone()
+    };
+    // mint's version replaces the mod's
+    assert_ne!(
+        get("FSD/Content/_AssemblyStorm/ModIntegration/MI_SpawnMods.uasset"),
+        b"mod"
+    );
+    assert_eq!(get("FSD/Content/Mod/Asset.uasset"), b"asset");
+}
+
+#[test]
+pub fn test_integrate_matches_patched_assets_lint() {
+    let dir = tempfile::tempdir().unwrap();
+    let game_pak = write_game_pak(dir.path(), &[("FSD/Content/Game/Other.uasset", b"")]);
+
+    let spec = ModSpecification {
+        url: "integration".to_string(),
+    };
+    let mod_path = write_mod_pak(
+        dir.path(),
+        "integration.pak",
+        "../../../",
+        &[
+            (
+                "FSD/Content/_AssemblyStorm/ModIntegration/MI_SpawnMods.uasset",
+                b"mod",
+            ),
+            (
+                "FSD/Content/_AssemblyStorm/ModIntegration/MI_Custom.uasset",
+                b"custom",
+            ),
+        ],
+    );
+
+    let report = mint::mod_lints::run_lints(
+        &[LintId::PATCHED_ASSETS].into(),
+        [(spec.clone(), mod_path.clone())].into(),
+        None,
+    )
+    .unwrap();
+    let finding = |path: &str| {
+        report
+            .findings_for(LintId::PATCHED_ASSETS)
+            .find(|f| f.path.as_deref() == Some(path))
+            .unwrap()
+    };
+    let replaced = finding("fsd/content/_assemblystorm/modintegration/mi_spawnmods.uasset");
+    assert_eq!(replaced.severity, Severity::Error);
+    assert!(replaced.message.contains("replaced by mint's version"));
+    let kept = finding("fsd/content/_assemblystorm/modintegration/mi_custom.uasset");
+    assert_eq!(kept.severity, Severity::Warning);
+
+    let out = dir.path().join("out");
+    mint::integrate::integrate(
+        &game_pak,
+        vec![(mod_info(&spec), ModConfig::new(spec.clone()), mod_path)],
+        &options(&out),
+    )
+    .unwrap();
+
+    // integration does what the lint says
+    let bundle = read_bundle(&out);
+    let get = |path: &str| {
+        bundle
+            .iter()
+            .find(|(p, _)| p == path)
+            .map(|(_, data)| data.clone())
+    };
+    assert_ne!(
+        get("FSD/Content/_AssemblyStorm/ModIntegration/MI_SpawnMods.uasset").unwrap(),
+        b"mod"
+    );
+    assert_eq!(
+        get("FSD/Content/_AssemblyStorm/ModIntegration/MI_Custom.uasset").unwrap(),
+        b"custom"
+    );
+}
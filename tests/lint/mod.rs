@@ -1,8 +1,68 @@
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use mint::mod_lints::{LintId, LintReport, SplitAssetPair};
-use mint::providers::ModSpecification;
+use mint::mod_lints::{
+    Lint, LintCtxt, LintFinding, LintId, LintReport, LintSuppression, Severity,
+    UnresolvedReference, UnresolvedReferencesLint,
+};
+use mint::providers::{
+    ApprovalStatus, ModInfo, ModResolution, ModSpecification, ModioTags, RequiredStatus,
+    ResolvableStatus,
+};
+
+/// Writes a mod pak containing `files` to `dir`, for lints that look at file contents or paths.
+pub(crate) fn write_mod_pak(
+    dir: &Path,
+    name: &str,
+    mount: &str,
+    files: &[(&str, &[u8])],
+) -> PathBuf {
+    let path = dir.join(name);
+    let mut pak = repak::PakBuilder::new().writer(
+        BufWriter::new(std::fs::File::create(&path).unwrap()),
+        repak::Version::V11,
+        mount.to_string(),
+        None,
+    );
+    for (file, data) in files {
+        pak.write_file(file, *data).unwrap();
+    }
+    pak.write_index().unwrap();
+    path
+}
+
+/// Start of a package summary with the given legacy and UE4 file versions.
+fn asset_header(legacy_file_version: i32, file_version_ue4: i32) -> Vec<u8> {
+    let mut header = 0x9E2A83C1u32.to_le_bytes().to_vec();
+    header.extend(legacy_file_version.to_le_bytes());
+    header.extend(0i32.to_le_bytes());
+    header.extend(file_version_ue4.to_le_bytes());
+    header.extend([0; 64]);
+    header
+}
+
+/// Paths of the findings of `lint` about `mod_spec`.
+fn finding_paths(
+    report: &LintReport,
+    lint: LintId,
+    mod_spec: &ModSpecification,
+) -> BTreeSet<String> {
+    report
+        .findings_for(lint)
+        .filter(|f| f.mods == [mod_spec.clone()])
+        .filter_map(|f| f.path.clone())
+        .collect()
+}
+
+/// Mods with findings of `lint`.
+fn finding_mods(report: &LintReport, lint: LintId) -> BTreeSet<ModSpecification> {
+    report
+        .findings_for(lint)
+        .flat_map(|f| f.mods.iter().cloned())
+        .collect()
+}
 
 #[test]
 pub fn test_lint_conflicting_files() {
@@ -21,15 +81,17 @@ pub fn test_lint_conflicting_files() {
     };
     let mods = [(a_spec.clone(), a_path), (b_spec.clone(), b_path)];
 
-    let LintReport {
-        conflicting_mods, ..
-    } = mint::mod_lints::run_lints(&[LintId::CONFLICTING].into(), mods.into(), None).unwrap();
+    let report =
+        mint::mod_lints::run_lints(&[LintId::CONFLICTING].into(), mods.into(), None).unwrap();
 
-    println!("{:#?}", conflicting_mods);
+    println!("{:#?}", report);
 
     assert_eq!(
-        conflicting_mods.unwrap().get("fsd/content/a.uexp"),
-        Some(&[a_spec, b_spec].into())
+        report
+            .findings_for(LintId::CONFLICTING)
+            .find(|f| f.path.as_deref() == Some("fsd/content/a.uexp"))
+            .map(|f| f.mods.clone()),
+        Some(vec![a_spec, b_spec])
     );
 }
 
@@ -49,15 +111,14 @@ pub fn test_lint_shader() {
     };
     let mods = [(a_spec.clone(), a_path), (b_spec.clone(), b_path)];
 
-    let LintReport {
-        shader_file_mods, ..
-    } = mint::mod_lints::run_lints(&[LintId::SHADER_FILES].into(), mods.into(), None).unwrap();
+    let report =
+        mint::mod_lints::run_lints(&[LintId::SHADER_FILES].into(), mods.into(), None).unwrap();
 
-    println!("{:#?}", shader_file_mods);
+    println!("{:#?}", report);
 
     assert_eq!(
-        shader_file_mods.unwrap().get(&a_spec),
-        Some(&["fsd/content/c.ushaderbytecode".to_string()].into())
+        finding_paths(&report, LintId::SHADER_FILES, &a_spec),
+        ["fsd/content/c.ushaderbytecode".to_string()].into()
     );
 }
 
@@ -77,17 +138,15 @@ pub fn test_lint_asset_registry_bin() {
     };
     let mods = [(a_spec.clone(), a_path), (b_spec.clone(), b_path)];
 
-    let LintReport {
-        asset_register_bin_mods,
-        ..
-    } = mint::mod_lints::run_lints(&[LintId::ASSET_REGISTRY_BIN].into(), mods.into(), None)
-        .unwrap();
+    let report =
+        mint::mod_lints::run_lints(&[LintId::ASSET_REGISTRY_BIN].into(), mods.into(), None)
+            .unwrap();
 
-    println!("{:#?}", asset_register_bin_mods);
+    println!("{:#?}", report);
 
     assert_eq!(
-        asset_register_bin_mods.unwrap().get(&a_spec),
-        Some(&["fsd/content/assetregistry.bin".to_string()].into())
+        finding_paths(&report, LintId::ASSET_REGISTRY_BIN, &a_spec),
+        ["fsd/content/assetregistry.bin".to_string()].into()
     );
 }
 
@@ -102,18 +161,19 @@ pub fn test_lint_outdated_pak_version() {
     };
     let mods = [(outdated_spec.clone(), outdated_pak_path)];
 
-    let LintReport {
-        outdated_pak_version_mods,
-        ..
-    } = mint::mod_lints::run_lints(&[LintId::OUTDATED_PAK_VERSION].into(), mods.into(), None)
-        .unwrap();
+    let report =
+        mint::mod_lints::run_lints(&[LintId::OUTDATED_PAK_VERSION].into(), mods.into(), None)
+            .unwrap();
 
-    println!("{:#?}", outdated_pak_version_mods);
+    println!("{:#?}", report);
 
-    assert_eq!(
-        outdated_pak_version_mods.unwrap().get(&outdated_spec),
-        Some(&repak::Version::V10)
-    );
+    let finding = report
+        .findings_for(LintId::OUTDATED_PAK_VERSION)
+        .find(|f| f.mods == [outdated_spec.clone()])
+        .unwrap();
+    assert!(finding
+        .message
+        .ends_with(&format!("outdated pak version {}", repak::Version::V10)));
 }
 
 #[test]
@@ -127,13 +187,12 @@ pub fn test_lint_empty_archive() {
     };
     let mods = [(empty_archive_spec.clone(), empty_archive_path)];
 
-    let LintReport {
-        empty_archive_mods, ..
-    } = mint::mod_lints::run_lints(&[LintId::EMPTY_ARCHIVE].into(), mods.into(), None).unwrap();
+    let report =
+        mint::mod_lints::run_lints(&[LintId::EMPTY_ARCHIVE].into(), mods.into(), None).unwrap();
 
-    println!("{:#?}", empty_archive_mods);
+    println!("{:#?}", report);
 
-    assert!(empty_archive_mods.unwrap().contains(&empty_archive_spec));
+    assert!(finding_mods(&report, LintId::EMPTY_ARCHIVE).contains(&empty_archive_spec));
 }
 
 #[test]
@@ -155,21 +214,18 @@ pub fn test_lint_only_non_pak_files() {
         (only_non_pak_spec.clone(), only_non_pak_path),
     ];
 
-    let LintReport {
-        archive_with_only_non_pak_files_mods,
-        ..
-    } = mint::mod_lints::run_lints(
+    let report = mint::mod_lints::run_lints(
         &[LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES].into(),
         mods.into(),
         None,
     )
     .unwrap();
 
-    println!("{:#?}", archive_with_only_non_pak_files_mods);
+    println!("{:#?}", report);
 
-    assert!(archive_with_only_non_pak_files_mods
-        .unwrap()
-        .contains(&only_non_pak_spec));
+    assert!(
+        finding_mods(&report, LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES).contains(&only_non_pak_spec)
+    );
 }
 
 #[test]
@@ -183,21 +239,81 @@ pub fn test_lint_multi_pak_archive() {
     };
     let mods = [(multiple_paks_spec.clone(), multiple_paks_archive_path)];
 
-    let LintReport {
-        archive_with_multiple_paks_mods,
-        ..
-    } = mint::mod_lints::run_lints(
+    let report = mint::mod_lints::run_lints(
         &[LintId::ARCHIVE_WITH_MULTIPLE_PAKS].into(),
         mods.into(),
         None,
     )
     .unwrap();
 
-    println!("{:#?}", archive_with_multiple_paks_mods);
+    println!("{:#?}", report);
 
-    assert!(archive_with_multiple_paks_mods
-        .unwrap()
-        .contains(&multiple_paks_spec));
+    assert!(finding_mods(&report, LintId::ARCHIVE_WITH_MULTIPLE_PAKS).contains(&multiple_paks_spec));
+}
+
+#[test]
+pub fn test_lint_every_pak_of_archive() {
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let first = write_mod_pak(
+        dir.path(),
+        "a.pak",
+        "../../../",
+        &[("FSD/Content/First.uasset", b"")],
+    );
+    let second = write_mod_pak(
+        dir.path(),
+        "b.pak",
+        "../../../",
+        &[
+            ("FSD/Content/Shared.uasset", b""),
+            ("Engine/Config/Foo.ini", b""),
+        ],
+    );
+    let zip_path = dir.path().join("multi.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+    for (name, path) in [("a.pak", first), ("b.pak", second)] {
+        zip.start_file(name, Default::default()).unwrap();
+        zip.write_all(&std::fs::read(path).unwrap()).unwrap();
+    }
+    zip.finish().unwrap();
+    let other_path = write_mod_pak(
+        dir.path(),
+        "other.pak",
+        "../../../",
+        &[("FSD/Content/Shared.uasset", b"")],
+    );
+
+    let multi_spec = ModSpecification {
+        url: "multi".to_string(),
+    };
+    let other_spec = ModSpecification {
+        url: "other".to_string(),
+    };
+    let mods = [
+        (multi_spec.clone(), zip_path),
+        (other_spec.clone(), other_path),
+    ];
+
+    let report = mint::mod_lints::run_lints(
+        &[LintId::CONFLICTING, LintId::BAD_PATHS].into(),
+        mods.into(),
+        None,
+    )
+    .unwrap();
+
+    println!("{:#?}", report);
+
+    // both findings are about the second pak of the archive
+    assert_eq!(
+        finding_mods(&report, LintId::CONFLICTING),
+        [multi_spec.clone(), other_spec].into()
+    );
+    assert_eq!(
+        finding_paths(&report, LintId::BAD_PATHS, &multi_spec),
+        ["engine/config/foo.ini".to_string()].into()
+    );
 }
 
 #[test]
@@ -211,16 +327,14 @@ pub fn test_lint_non_asset_files() {
     };
     let mods = [(non_asset_files_spec.clone(), non_asset_files_path)];
 
-    let LintReport {
-        non_asset_file_mods,
-        ..
-    } = mint::mod_lints::run_lints(&[LintId::NON_ASSET_FILES].into(), mods.into(), None).unwrap();
+    let report =
+        mint::mod_lints::run_lints(&[LintId::NON_ASSET_FILES].into(), mods.into(), None).unwrap();
 
-    println!("{:#?}", non_asset_file_mods);
+    println!("{:#?}", report);
 
     assert_eq!(
-        non_asset_file_mods.unwrap().get(&non_asset_files_spec),
-        Some(&["never_gonna_give_you_up.txt".to_string()].into())
+        finding_paths(&report, LintId::NON_ASSET_FILES, &non_asset_files_spec),
+        ["never_gonna_give_you_up.txt".to_string()].into()
     );
 }
 
@@ -235,29 +349,21 @@ pub fn test_lint_split_asset_pairs() {
     };
     let mods = [(split_asset_pairs_spec.clone(), split_asset_pairs_path)];
 
-    let LintReport {
-        split_asset_pairs_mods,
-        ..
-    } = mint::mod_lints::run_lints(&[LintId::SPLIT_ASSET_PAIRS].into(), mods.into(), None).unwrap();
+    let report =
+        mint::mod_lints::run_lints(&[LintId::SPLIT_ASSET_PAIRS].into(), mods.into(), None).unwrap();
 
-    println!("{:#?}", split_asset_pairs_mods);
+    println!("{:#?}", report);
 
-    assert_eq!(
-        split_asset_pairs_mods.unwrap().get(&split_asset_pairs_spec),
-        Some(
-            &[
-                (
-                    "missing_uasset/a.uexp".to_string(),
-                    SplitAssetPair::MissingUasset
-                ),
-                (
-                    "missing_uexp/b.uasset".to_string(),
-                    SplitAssetPair::MissingUexp
-                )
-            ]
-            .into()
-        )
-    );
+    let findings = report
+        .findings_for(LintId::SPLIT_ASSET_PAIRS)
+        .filter(|f| f.mods == [split_asset_pairs_spec.clone()])
+        .map(|f| (f.path.clone().unwrap(), f.message.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].0, "missing_uasset/a.uexp");
+    assert!(findings[0].1.ends_with("missing matching .uasset file"));
+    assert_eq!(findings[1].0, "missing_uexp/b.uasset");
+    assert!(findings[1].1.ends_with("missing matching .uexp file"));
 }
 
 #[test]
@@ -276,22 +382,934 @@ pub fn test_lint_unmodified_game_assets() {
         unmodified_game_assets_path,
     )];
 
-    let LintReport {
-        unmodified_game_assets_mods,
-        ..
-    } = mint::mod_lints::run_lints(
+    let report = mint::mod_lints::run_lints(
         &[LintId::UNMODIFIED_GAME_ASSETS].into(),
         mods.into(),
         Some(reference_pak_path),
     )
     .unwrap();
 
-    println!("{:#?}", unmodified_game_assets_mods);
+    println!("{:#?}", report);
+
+    assert_eq!(
+        finding_paths(
+            &report,
+            LintId::UNMODIFIED_GAME_ASSETS,
+            &unmodified_game_assets_spec
+        ),
+        ["a.uexp".to_string(), "a.uasset".to_string()].into()
+    );
+}
+
+#[test]
+pub fn test_lint_corrupt_assets() {
+    let dir = tempfile::tempdir().unwrap();
+    let ue5 = asset_header(-8, 522);
+    let old = asset_header(-7, 500);
+    let corrupt_spec = ModSpecification {
+        url: "corrupt".to_string(),
+    };
+    let corrupt_path = write_mod_pak(
+        dir.path(),
+        "corrupt.pak",
+        "../../../",
+        &[
+            ("FSD/Content/Garbage.uasset", b"garbage"),
+            ("FSD/Content/Garbage.uexp", b"garbage"),
+            ("FSD/Content/UE5.uasset", &ue5),
+            ("FSD/Content/UE5.uexp", b""),
+            ("FSD/Content/Old.uasset", &old),
+            ("FSD/Content/Old.uexp", b""),
+            ("FSD/Content/Lonely.uasset", b"garbage"),
+        ],
+    );
+    let mods = [(corrupt_spec.clone(), corrupt_path)];
+
+    let report =
+        mint::mod_lints::run_lints(&[LintId::CORRUPT_ASSETS].into(), mods.into(), None).unwrap();
+
+    println!("{:#?}", report);
+
+    assert_eq!(
+        finding_paths(&report, LintId::CORRUPT_ASSETS, &corrupt_spec),
+        [
+            "fsd/content/garbage.uasset".to_string(),
+            "fsd/content/ue5.uasset".to_string(),
+            "fsd/content/old.uasset".to_string(),
+        ]
+        .into()
+    );
+    assert!(report
+        .findings_for(LintId::CORRUPT_ASSETS)
+        .any(|f| f.message.contains("Unreal Engine 5")));
+}
+
+#[test]
+pub fn test_lint_bad_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let bad_paths_spec = ModSpecification {
+        url: "bad_paths".to_string(),
+    };
+    let bad_paths_path = write_mod_pak(
+        dir.path(),
+        "bad_paths.pak",
+        "../../../",
+        &[
+            ("FSD/Content/Fine.uasset", b""),
+            ("Engine/Config/Foo.ini", b""),
+            ("../../escape.txt", b""),
+        ],
+    );
+    let mounted_spec = ModSpecification {
+        url: "mounted".to_string(),
+    };
+    let mounted_path = write_mod_pak(
+        dir.path(),
+        "mounted.pak",
+        "../../../FSD/Content/",
+        &[("Fine.uasset", b"")],
+    );
+    let mods = [
+        (bad_paths_spec.clone(), bad_paths_path),
+        (mounted_spec.clone(), mounted_path),
+    ];
+
+    let report =
+        mint::mod_lints::run_lints(&[LintId::BAD_PATHS].into(), mods.into(), None).unwrap();
+
+    println!("{:#?}", report);
+
+    assert_eq!(
+        finding_paths(&report, LintId::BAD_PATHS, &bad_paths_spec),
+        [
+            "engine/config/foo.ini".to_string(),
+            "../../escape.txt".to_string(),
+        ]
+        .into()
+    );
+    let mounted = report
+        .findings_for(LintId::BAD_PATHS)
+        .filter(|f| f.mods == [mounted_spec.clone()])
+        .collect::<Vec<_>>();
+    assert_eq!(mounted.len(), 1);
+    assert_eq!(mounted[0].path, None);
+}
+
+#[test]
+pub fn test_lint_skips_bad_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = ModSpecification {
+        url: "escaping".to_string(),
+    };
+    let path = write_mod_pak(
+        dir.path(),
+        "escaping.pak",
+        "../../../",
+        &[("../../../../escape.txt", b"")],
+    );
+    let mods = [(spec.clone(), path)];
+
+    let report =
+        mint::mod_lints::run_lints(&[LintId::NON_ASSET_FILES].into(), mods.into(), None).unwrap();
+
+    assert!(finding_paths(&report, LintId::NON_ASSET_FILES, &spec).is_empty());
+}
+
+#[test]
+pub fn test_lint_unresolved_references_skips_corrupt_assets() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = ModSpecification {
+        url: "corrupt".to_string(),
+    };
+    let path = write_mod_pak(
+        dir.path(),
+        "corrupt.pak",
+        "../../../",
+        &[
+            ("FSD/Content/Garbage.uasset", b"garbage"),
+            ("FSD/Content/Garbage.uexp", b"garbage"),
+        ],
+    );
+    let mods = [(spec.clone(), path)];
+
+    let report = mint::mod_lints::run_lints(
+        &[LintId::UNRESOLVED_REFERENCES].into(),
+        mods.into(),
+        Some(PathBuf::from("test_assets/lints/reference.pak")),
+    )
+    .unwrap();
+
+    assert!(report
+        .findings_for(LintId::UNRESOLVED_REFERENCES)
+        .next()
+        .is_none());
+}
+
+#[test]
+pub fn test_lint_unresolved_references() {
+    // imports /Game/Game/Existing, /Game/Other/Provided, /Game/Refs/Self, /Game/Missing/Dep,
+    // /Game/Nowhere/Gone and /Script/Engine
+    let base_path = PathBuf::from("test_assets/lints/unresolved_references");
+    let uasset = std::fs::read(base_path.join("Asset.uasset")).unwrap();
+    let uexp = std::fs::read(base_path.join("Asset.uexp")).unwrap();
 
+    let dir = tempfile::tempdir().unwrap();
+    let game_path = write_mod_pak(
+        dir.path(),
+        "FSD-WindowsNoEditor.pak",
+        "../../../",
+        &[("FSD/Content/Game/Existing.uasset", b"")],
+    );
+    let refs_spec = ModSpecification {
+        url: "refs".to_string(),
+    };
+    let refs_path = write_mod_pak(
+        dir.path(),
+        "refs.pak",
+        "../../../",
+        &[
+            ("FSD/Content/Refs/Asset.uasset", uasset.as_slice()),
+            ("FSD/Content/Refs/Asset.uexp", uexp.as_slice()),
+            ("FSD/Content/Refs/Self.uasset", b""),
+        ],
+    );
+    let other_spec = ModSpecification {
+        url: "other".to_string(),
+    };
+    let other_path = write_mod_pak(
+        dir.path(),
+        "other.pak",
+        "../../../",
+        &[("FSD/Content/Other/Provided.uasset", b"")],
+    );
+    let candidate_spec = ModSpecification {
+        url: "candidate".to_string(),
+    };
+    let candidate_path = write_mod_pak(
+        dir.path(),
+        "candidate.pak",
+        "../../../",
+        &[("FSD/Content/Missing/Dep.uasset", b"")],
+    );
+
+    let lint_ctxt = LintCtxt::init(
+        [(refs_spec.clone(), refs_path), (other_spec, other_path)].into(),
+        Some(game_path),
+    )
+    .unwrap()
+    .with_candidate_mods([(candidate_spec.clone(), candidate_path)].into());
+
+    let unresolved = UnresolvedReferencesLint.check_mods(&lint_ctxt).unwrap();
+    let referenced_by = BTreeSet::from(["fsd/content/refs/asset.uasset".to_string()]);
+    assert_eq!(
+        unresolved,
+        [(
+            refs_spec.clone(),
+            [
+                (
+                    "/game/missing/dep".to_string(),
+                    UnresolvedReference {
+                        referenced_by: referenced_by.clone(),
+                        candidates: [candidate_spec].into(),
+                    }
+                ),
+                (
+                    "/game/nowhere/gone".to_string(),
+                    UnresolvedReference {
+                        referenced_by,
+                        candidates: BTreeSet::new(),
+                    }
+                ),
+            ]
+            .into()
+        )]
+        .into()
+    );
+
+    let report =
+        mint::mod_lints::run_lints_with_ctxt(&[LintId::UNRESOLVED_REFERENCES].into(), &lint_ctxt)
+            .unwrap();
+    assert_eq!(
+        finding_paths(&report, LintId::UNRESOLVED_REFERENCES, &refs_spec),
+        BTreeSet::from([
+            "/game/missing/dep".to_string(),
+            "/game/nowhere/gone".to_string()
+        ])
+    );
+    assert!(report
+        .findings_for(LintId::UNRESOLVED_REFERENCES)
+        .any(|f| f.path.as_deref() == Some("/game/missing/dep")
+            && f.message
+                .ends_with("enable one of these mods to provide it: candidate")));
+}
+
+#[test]
+pub fn test_lint_patched_assets() {
+    let dir = tempfile::tempdir().unwrap();
+    let high_spec = ModSpecification {
+        url: "high".to_string(),
+    };
+    let low_spec = ModSpecification {
+        url: "low".to_string(),
+    };
+    let high_path = write_mod_pak(
+        dir.path(),
+        "high.pak",
+        "../../../",
+        &[
+            ("FSD/Content/Game/BP_PlayerControllerBase.uasset", b""),
+            ("FSD/Content/Game/BP_PlayerControllerBase.uexp", b""),
+            (
+                "FSD/Content/_AssemblyStorm/ModIntegration/MI_SpawnMods.uasset",
+                b"",
+            ),
+            (
+                "FSD/Content/_AssemblyStorm/ModIntegration/MI_Custom.uasset",
+                b"",
+            ),
+        ],
+    );
+    let low_path = write_mod_pak(
+        dir.path(),
+        "low.pak",
+        "../../../",
+        &[
+            ("FSD/Content/Game/BP_PlayerControllerBase.uasset", b""),
+            ("FSD/Content/Game/BP_PlayerControllerBase.uexp", b""),
+            ("FSD/Content/Game/BP_Unpatched.uasset", b""),
+        ],
+    );
+    let mods = [(high_spec.clone(), high_path), (low_spec.clone(), low_path)];
+
+    let report =
+        mint::mod_lints::run_lints(&[LintId::PATCHED_ASSETS].into(), mods.into(), None).unwrap();
+
+    println!("{:#?}", report);
+
+    let findings = report
+        .findings_for(LintId::PATCHED_ASSETS)
+        .collect::<Vec<_>>();
+    assert_eq!(findings.len(), 3);
+    let pcb = findings
+        .iter()
+        .find(|f| f.path.as_deref() == Some("fsd/content/game/bp_playercontrollerbase"))
+        .unwrap();
+    assert_eq!(pcb.mods, [high_spec.clone(), low_spec]);
+    assert!(pcb.message.contains("the version from low is patched"));
     assert_eq!(
-        unmodified_game_assets_mods
+        finding_paths(&report, LintId::PATCHED_ASSETS, &high_spec),
+        [
+            "fsd/content/_assemblystorm/modintegration/mi_custom.uasset".to_string(),
+            "fsd/content/_assemblystorm/modintegration/mi_spawnmods.uasset".to_string()
+        ]
+        .into()
+    );
+    // only files mint ships are replaced by its own version
+    let severity = |path: &str| {
+        findings
+            .iter()
+            .find(|f| f.path.as_deref() == Some(path))
             .unwrap()
-            .get(&unmodified_game_assets_spec),
-        Some(&["a.uexp".to_string(), "a.uasset".to_string()].into())
+            .severity
+    };
+    assert_eq!(
+        severity("fsd/content/_assemblystorm/modintegration/mi_spawnmods.uasset"),
+        Severity::Error
+    );
+    assert_eq!(
+        severity("fsd/content/_assemblystorm/modintegration/mi_custom.uasset"),
+        Severity::Warning
+    );
+}
+
+#[test]
+pub fn test_lint_init_actors() {
+    let dir = tempfile::tempdir().unwrap();
+    let a_spec = ModSpecification {
+        url: "a".to_string(),
+    };
+    let b_spec = ModSpecification {
+        url: "b".to_string(),
+    };
+    let a_path = write_mod_pak(
+        dir.path(),
+        "a.pak",
+        "../../../",
+        &[
+            ("FSD/Content/A/InitSpaceRig.uasset", b""),
+            ("FSD/Content/A/Init_Cave.uasset", b""),
+            ("FSD/Content/A/BP_InitSpaceRigs.uasset", b""),
+            ("FSD/Content/A/InitializeWeapons.uasset", b""),
+            ("FSD/InitCave.uasset", b""),
+        ],
+    );
+    let b_path = write_mod_pak(
+        dir.path(),
+        "b.pak",
+        "../../../",
+        &[("FSD/Content/A/initspacerig.uasset", b"")],
+    );
+    let mods = [(a_spec.clone(), a_path), (b_spec.clone(), b_path)];
+
+    let report =
+        mint::mod_lints::run_lints(&[LintId::INIT_ACTORS].into(), mods.into(), None).unwrap();
+
+    println!("{:#?}", report);
+
+    assert_eq!(
+        finding_paths(&report, LintId::INIT_ACTORS, &a_spec),
+        [
+            "fsd/content/a/init_cave.uasset".to_string(),
+            "fsd/content/a/bp_initspacerigs.uasset".to_string(),
+            "fsd/initcave.uasset".to_string(),
+        ]
+        .into()
+    );
+    assert!(finding_paths(&report, LintId::INIT_ACTORS, &b_spec).is_empty());
+    let duplicate = report
+        .findings_for(LintId::INIT_ACTORS)
+        .find(|f| f.path.as_deref() == Some("fsd/content/a/initspacerig.uasset"))
+        .unwrap();
+    assert_eq!(duplicate.mods, [a_spec, b_spec]);
+}
+
+/// Metadata of a mod.io mod tagged with `versions`.
+fn modio_info(spec: &ModSpecification, versions: &[&str]) -> ModInfo {
+    ModInfo {
+        provider: "modio",
+        name: spec.url.clone(),
+        spec: spec.clone(),
+        versions: vec![],
+        version: None,
+        resolution: ModResolution {
+            url: spec.url.clone(),
+            status: ResolvableStatus::Resolvable,
+        },
+        suggested_require: false,
+        suggested_dependencies: vec![],
+        modio_tags: Some(ModioTags {
+            qol: false,
+            gameplay: false,
+            audio: false,
+            visual: false,
+            framework: false,
+            versions: versions.iter().map(|v| v.to_string()).collect(),
+            required_status: RequiredStatus::Optional,
+            approval_status: ApprovalStatus::Approved,
+        }),
+        modio_id: None,
+    }
+}
+
+#[test]
+pub fn test_lint_game_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let game_pak_path = write_mod_pak(
+        dir.path(),
+        "FSD-WindowsNoEditor.pak",
+        "../../../",
+        &[(
+            "FSD/Config/DefaultGame.ini",
+            b"[/Script/EngineSettings.GeneralProjectSettings]\r\nProjectVersion=1.38.89736.0\r\n",
+        )],
+    );
+    let specs = ["current", "outdated", "untagged"].map(|url| ModSpecification {
+        url: url.to_string(),
+    });
+    let mods = specs
+        .iter()
+        .map(|spec| {
+            let path = write_mod_pak(dir.path(), &format!("{}.pak", spec.url), "../../../", &[]);
+            (spec.clone(), path)
+        })
+        .collect();
+    let mod_infos = [
+        (specs[0].clone(), modio_info(&specs[0], &["1.37", "1.38"])),
+        (specs[1].clone(), modio_info(&specs[1], &["1.37"])),
+        (specs[2].clone(), modio_info(&specs[2], &[])),
+    ]
+    .into();
+
+    let lint_ctxt = LintCtxt::init(mods, Some(game_pak_path))
+        .unwrap()
+        .with_mod_infos(mod_infos);
+    let report =
+        mint::mod_lints::run_lints_with_ctxt(&[LintId::GAME_VERSION].into(), &lint_ctxt).unwrap();
+
+    println!("{:#?}", report);
+
+    assert_eq!(
+        finding_mods(&report, LintId::GAME_VERSION),
+        [specs[1].clone()].into()
+    );
+    assert!(report
+        .findings_for(LintId::GAME_VERSION)
+        .all(|f| f.message.contains("1.38.89736.0")));
+}
+
+#[test]
+pub fn test_lint_dangerous_files() {
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut pe = b"MZ".to_vec();
+    pe.resize(0x3c, 0);
+    pe.extend(0x40u32.to_le_bytes());
+    pe.extend(b"PE\0\0");
+    let pak_path = write_mod_pak(
+        dir.path(),
+        "dangerous.pak",
+        "../../../",
+        &[
+            ("FSD/Content/Evil.uasset", &pe),
+            ("FSD/Content/Run.bat", b"@echo off"),
+            ("FSD/Content/Fine.uasset", b""),
+        ],
+    );
+
+    let pak = std::fs::read(pak_path).unwrap();
+    let zip_path = dir.path().join("dangerous.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+    let files: [(&str, &[u8]); 4] = [
+        ("dangerous.pak", &pak),
+        ("x3daudio1_7.dll", b""),
+        ("nested.zip", b"PK\x03\x04"),
+        ("readme.txt", b"hello"),
+    ];
+    for (name, data) in files {
+        zip.start_file(name, Default::default()).unwrap();
+        zip.write_all(data).unwrap();
+    }
+    zip.finish().unwrap();
+
+    let spec = ModSpecification {
+        url: "dangerous".to_string(),
+    };
+    let mods = [(spec.clone(), zip_path)];
+
+    let report =
+        mint::mod_lints::run_lints(&[LintId::DANGEROUS_FILES].into(), mods.into(), None).unwrap();
+
+    println!("{:#?}", report);
+
+    assert_eq!(
+        finding_paths(&report, LintId::DANGEROUS_FILES, &spec),
+        [
+            "fsd/content/evil.uasset".to_string(),
+            "fsd/content/run.bat".to_string(),
+            "x3daudio1_7.dll".to_string(),
+            "nested.zip".to_string(),
+        ]
+        .into()
+    );
+    assert!(report
+        .findings_for(LintId::DANGEROUS_FILES)
+        .any(|f| f.message.contains("DLL mint installs")));
+}
+
+#[test]
+pub fn test_lint_duplicate_content() {
+    let dir = tempfile::tempdir().unwrap();
+    let specs = ["original", "reupload", "bundler"].map(|url| ModSpecification {
+        url: url.to_string(),
+    });
+    let original: [(&str, &[u8]); 5] = [
+        ("FSD/Content/A.uasset", b"a"),
+        ("FSD/Content/B.uasset", b"b"),
+        ("FSD/Content/C.uasset", b"c"),
+        ("FSD/Content/D.uasset", b"d"),
+        ("FSD/Content/Lib/Lib.uasset", b"lib"),
+    ];
+    let mut reupload = original.to_vec();
+    reupload.push(("FSD/Content/E.uasset", b"e"));
+    let bundler: [(&str, &[u8]); 2] = [
+        ("FSD/Content/Bundled/Lib.uasset", b"lib"),
+        ("FSD/Content/Empty.uasset", b""),
+    ];
+    let mods = specs
+        .iter()
+        .zip([&original[..], &reupload[..], &bundler[..]])
+        .map(|(spec, files)| {
+            let path = write_mod_pak(dir.path(), &format!("{}.pak", spec.url), "../../../", files);
+            (spec.clone(), path)
+        })
+        .collect::<Vec<_>>();
+
+    let report = mint::mod_lints::run_lints(
+        &[LintId::DUPLICATE_CONTENT].into(),
+        mods.into_iter().collect(),
+        None,
+    )
+    .unwrap();
+
+    println!("{:#?}", report);
+
+    let findings = report
+        .findings_for(LintId::DUPLICATE_CONTENT)
+        .collect::<Vec<_>>();
+    assert_eq!(findings.len(), 2);
+    assert!(findings
+        .iter()
+        .any(|f| f.mods == [specs[0].clone(), specs[1].clone()] && f.path.is_none()));
+    assert!(findings
+        .iter()
+        .any(|f| f.mods == specs && f.path.as_deref() == Some("fsd/content/lib/lib.uasset")));
+}
+
+#[test]
+pub fn test_lint_fix() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = ModSpecification {
+        url: "registry".to_string(),
+    };
+    let path = write_mod_pak(
+        dir.path(),
+        "registry.pak",
+        "../../../FSD/",
+        &[
+            ("AssetRegistry.bin", &b"registry"[..]),
+            ("Content/A.uasset", &b"a"[..]),
+        ],
+    );
+    let mods = [(spec.clone(), path)];
+
+    let report = mint::mod_lints::run_lints(
+        &[LintId::ASSET_REGISTRY_BIN, LintId::SPLIT_ASSET_PAIRS].into(),
+        mods.into(),
+        None,
+    )
+    .unwrap();
+    // only findings of fixable lints are removed
+    assert!(report.findings_for(LintId::SPLIT_ASSET_PAIRS).count() > 0);
+
+    let store = mint::providers::ModStore::new(dir.path(), &Default::default()).unwrap();
+    let fixed_mods = mint::mod_lints::fix_mods(&report, &store).unwrap();
+
+    assert_eq!(fixed_mods.len(), 1);
+    assert_eq!(fixed_mods[0].mod_spec, spec);
+    assert_eq!(
+        fixed_mods[0].removed,
+        ["fsd/assetregistry.bin".to_string()].into()
+    );
+
+    let mut reader = std::io::BufReader::new(std::fs::File::open(&fixed_mods[0].path).unwrap());
+    let pak = repak::PakBuilder::new().reader(&mut reader).unwrap();
+    assert_eq!(pak.mount_point(), "../../../FSD/");
+    assert_eq!(pak.version(), repak::Version::V11);
+    assert_eq!(pak.files(), ["Content/A.uasset".to_string()]);
+}
+
+#[test]
+pub fn test_lint_fix_multi_pak_archive() {
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let first = write_mod_pak(
+        dir.path(),
+        "a.pak",
+        "../../../",
+        &[("FSD/Content/A.uasset", b"a"), ("FSD/Content/A.uexp", b"a")],
+    );
+    let second = write_mod_pak(
+        dir.path(),
+        "b.pak",
+        "../../../",
+        &[
+            ("FSD/AssetRegistry.bin", b"registry"),
+            ("FSD/Content/B.uasset", b"b"),
+        ],
+    );
+    let zip_path = dir.path().join("multi.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+    for (name, path) in [("a.pak", first), ("sub/b.pak", second)] {
+        zip.start_file(name, Default::default()).unwrap();
+        zip.write_all(&std::fs::read(path).unwrap()).unwrap();
+    }
+    zip.finish().unwrap();
+    let spec = ModSpecification {
+        url: "multi".to_string(),
+    };
+    // can't be cleaned, which doesn't stop the other mod from being fixed
+    let broken_spec = ModSpecification {
+        url: "broken".to_string(),
+    };
+    let broken_path = dir.path().join("broken.pak");
+
+    let mut report = mint::mod_lints::run_lints(
+        &[LintId::ASSET_REGISTRY_BIN].into(),
+        [(spec.clone(), zip_path)].into(),
+        None,
+    )
+    .unwrap();
+    report.mod_paths.insert(broken_spec.clone(), broken_path);
+    report.findings.push(LintFinding {
+        mods: vec![broken_spec],
+        ..report.findings[0].clone()
+    });
+
+    let store = mint::providers::ModStore::new(dir.path(), &Default::default()).unwrap();
+    let fixed_mods = mint::mod_lints::fix_mods(&report, &store).unwrap();
+
+    assert_eq!(fixed_mods.len(), 1);
+    assert_eq!(fixed_mods[0].mod_spec, spec);
+    assert_eq!(
+        fixed_mods[0].removed,
+        ["fsd/assetregistry.bin".to_string()].into()
+    );
+
+    // every pak is kept under its name
+    let mut archive =
+        zip::ZipArchive::new(std::fs::File::open(&fixed_mods[0].path).unwrap()).unwrap();
+    let mut files = BTreeSet::new();
+    for name in ["a.pak", "sub/b.pak"] {
+        let mut data = vec![];
+        std::io::Read::read_to_end(&mut archive.by_name(name).unwrap(), &mut data).unwrap();
+        let mut reader = std::io::Cursor::new(data);
+        let pak = repak::PakBuilder::new().reader(&mut reader).unwrap();
+        files.extend(pak.files());
+    }
+    assert_eq!(
+        files,
+        [
+            "FSD/Content/A.uasset".to_string(),
+            "FSD/Content/A.uexp".to_string(),
+            "FSD/Content/B.uasset".to_string()
+        ]
+        .into()
+    );
+}
+
+#[test]
+pub fn test_lint_disabled_paks() {
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let first = write_mod_pak(
+        dir.path(),
+        "a.pak",
+        "../../../",
+        &[("FSD/Content/A.uasset", b"a")],
+    );
+    let second = write_mod_pak(
+        dir.path(),
+        "b.pak",
+        "../../../",
+        &[("FSD/AssetRegistry.bin", b"registry")],
     );
+    let zip_path = dir.path().join("multi.zip");
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+    for (name, path) in [("a.pak", first), ("sub/b.pak", second)] {
+        zip.start_file(name, Default::default()).unwrap();
+        zip.write_all(&std::fs::read(path).unwrap()).unwrap();
+    }
+    zip.finish().unwrap();
+    let spec = ModSpecification {
+        url: "multi".to_string(),
+    };
+
+    let lints = [LintId::ASSET_REGISTRY_BIN].into();
+    let report = mint::mod_lints::run_lints_with_ctxt(
+        &lints,
+        &LintCtxt::init([(spec.clone(), zip_path.clone())].into(), None).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        finding_mods(&report, LintId::ASSET_REGISTRY_BIN),
+        [spec.clone()].into()
+    );
+
+    // the pak shipping the file isn't integrated
+    let lint_ctxt = LintCtxt::init([(spec.clone(), zip_path)].into(), None)
+        .unwrap()
+        .with_disabled_paks([(spec, ["sub/b.pak".to_string()].into())].into());
+    let report = mint::mod_lints::run_lints_with_ctxt(&lints, &lint_ctxt).unwrap();
+    assert!(report.findings.is_empty());
+}
+
+#[test]
+pub fn test_lint_suppressions_and_baseline() {
+    let dir = tempfile::tempdir().unwrap();
+    let framework = ModSpecification {
+        url: "framework".to_string(),
+    };
+    let other = ModSpecification {
+        url: "other".to_string(),
+    };
+    let mods = [
+        (
+            framework.clone(),
+            write_mod_pak(
+                dir.path(),
+                "framework.pak",
+                "../../../",
+                &[
+                    ("FSD/Content/config.json", &b"{}"[..]),
+                    ("FSD/Content/readme.txt", &b""[..]),
+                ],
+            ),
+        ),
+        (
+            other.clone(),
+            write_mod_pak(
+                dir.path(),
+                "other.pak",
+                "../../../",
+                &[("FSD/Content/notes.txt", b"")],
+            ),
+        ),
+    ];
+
+    let suppressions = [
+        LintSuppression {
+            lint: LintId::NON_ASSET_FILES.name().to_string(),
+            mods: [framework.clone()].into(),
+            path: Some("fsd/content/config.json".to_string()),
+            all_paths: false,
+        },
+        LintSuppression {
+            lint: LintId::NON_ASSET_FILES.name().to_string(),
+            mods: [other.clone()].into(),
+            path: None,
+            all_paths: true,
+        },
+        // only matches findings without a path
+        LintSuppression {
+            lint: LintId::NON_ASSET_FILES.name().to_string(),
+            mods: [framework.clone()].into(),
+            path: None,
+            all_paths: false,
+        },
+    ];
+    let lint_ctxt = LintCtxt::init(mods.into(), None)
+        .unwrap()
+        .with_suppressions(suppressions.into());
+    let mut report =
+        mint::mod_lints::run_lints_with_ctxt(&[LintId::NON_ASSET_FILES].into(), &lint_ctxt)
+            .unwrap();
+
+    println!("{:#?}", report);
+
+    assert_eq!(
+        finding_paths(&report, LintId::NON_ASSET_FILES, &framework),
+        ["fsd/content/readme.txt".to_string()].into()
+    );
+    assert!(finding_paths(&report, LintId::NON_ASSET_FILES, &other).is_empty());
+    assert_eq!(report.suppressed.len(), 2);
+
+    let baseline = report.baseline();
+    assert!(report.findings.iter().all(|f| !f.is_new(Some(&baseline))));
+    assert!(report.findings.iter().all(|f| !f.is_new(None)));
+    // messages can change without the finding being new
+    let reworded = LintFinding {
+        message: "reworded".to_string(),
+        ..report.findings[0].clone()
+    };
+    assert!(!reworded.is_new(Some(&baseline)));
+
+    // suppressions match the exact set of mods of a finding
+    let shared = LintFinding {
+        mods: vec![other.clone(), framework.clone()],
+        ..report.findings[0].clone()
+    };
+    assert!(!LintSuppression::for_finding(&report.findings[0]).matches(&shared));
+    assert!(LintSuppression::for_lint(&shared).matches(&LintFinding {
+        mods: vec![framework.clone(), other.clone()],
+        path: Some("fsd/content/other.txt".to_string()),
+        ..shared.clone()
+    }));
+    // baseline keys ignore the order of mods as well
+    assert_eq!(
+        shared.key(),
+        LintFinding {
+            mods: vec![framework.clone(), other.clone()],
+            ..shared.clone()
+        }
+        .key()
+    );
+
+    // findings suppressed after the report was generated are moved out of it
+    let suppression = LintSuppression::for_finding(&report.findings[0]);
+    report.suppress(&suppression);
+    assert!(report.findings.is_empty());
+    assert_eq!(report.suppressed.len(), 3);
+}
+
+#[test]
+pub fn test_lint_report_export() {
+    let dir = tempfile::tempdir().unwrap();
+    let a_spec = ModSpecification {
+        url: "<a>".to_string(),
+    };
+    let b_spec = ModSpecification {
+        url: "b".to_string(),
+    };
+    let mods = [
+        (
+            a_spec.clone(),
+            write_mod_pak(
+                dir.path(),
+                "a.pak",
+                "../../../",
+                &[("FSD/Content/notes.txt", b"")],
+            ),
+        ),
+        (
+            b_spec.clone(),
+            write_mod_pak(
+                dir.path(),
+                "b.pak",
+                "../../../",
+                &[("FSD/Content/B.uasset", b""), ("FSD/Content/B.uexp", b"")],
+            ),
+        ),
+    ];
+
+    let report =
+        mint::mod_lints::run_lints(&[LintId::NON_ASSET_FILES].into(), mods.into(), None).unwrap();
+
+    let markdown = report.to_markdown();
+    println!("{markdown}");
+    assert!(markdown.contains("0 errors, 1 warnings, 0 info"));
+    assert!(markdown.contains("- **warning** <a> includes non-asset file `fsd/content/notes.txt`"));
+
+    let html = report.to_html();
+    println!("{html}");
+    assert!(html.contains("<h2>&lt;a&gt;</h2>"));
+    assert!(html.contains("<code>fsd/content/notes.txt</code>"));
+    // mods without findings still get a section
+    assert!(html.contains("<h2>b</h2>\n<p>No findings.</p>"));
+}
+
+#[test]
+pub fn test_game_pak_index_cache() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let game_pak_path = Path::new("test_assets/lints/reference.pak");
+
+    let index =
+        mint::game_pak_index::GamePakIndex::load_or_build(game_pak_path, cache_dir.path()).unwrap();
+    let hash = index.get("a.uasset").unwrap().to_string();
+
+    let index_paths = std::fs::read_dir(cache_dir.path().join("game_pak_index"))
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(index_paths.len(), 1);
+
+    // the saved index is used as long as the game pak doesn't change
+    let mut saved: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&index_paths[0]).unwrap()).unwrap();
+    saved["hashes"]["a.uasset"] = "cached".into();
+    std::fs::write(&index_paths[0], serde_json::to_vec(&saved).unwrap()).unwrap();
+
+    let index =
+        mint::game_pak_index::GamePakIndex::load_or_build(game_pak_path, cache_dir.path()).unwrap();
+    assert_eq!(index.get("a.uasset"), Some("cached"));
+
+    // indexes written by other versions are rebuilt
+    saved["version"] = 0.into();
+    std::fs::write(&index_paths[0], serde_json::to_vec(&saved).unwrap()).unwrap();
+
+    let index =
+        mint::game_pak_index::GamePakIndex::load_or_build(game_pak_path, cache_dir.path()).unwrap();
+    assert_eq!(index.get("a.uasset"), Some(hash.as_str()));
 }
@@ -1 +1,2 @@
+mod integrate;
 mod lint;